@@ -0,0 +1,93 @@
+//! Compares the `Arc<dyn VirtualJoystickBehavior>` path with behavior components.
+//!
+//! Run with `cargo bench --bench behaviors`.
+
+use std::time::{Duration, Instant};
+
+use bevy::{ecs::schedule::ScheduleLabel, prelude::*};
+use virtual_joystick::*;
+
+const JOYSTICKS: usize = 500;
+const FRAMES: u32 = 1000;
+
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+struct Behaviors;
+
+/// App running only the delta pipeline for [`JOYSTICKS`] dragged joysticks in [`Behaviors`].
+fn app(component_behaviors: bool) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        VirtualJoystickPlugin::<JoystickId>::default().in_schedule(Behaviors),
+    ))
+    .init_resource::<UiScale>();
+    app.finish();
+    app.cleanup();
+
+    let world = app.world_mut();
+    for id in 0..JOYSTICKS {
+        let mut touch_state = TouchState::from_touch_pos(id as u64, Vec2::new(40., 20.));
+        touch_state.just_pressed = false;
+        let mut joystick = world.spawn((
+            VirtualJoystickNode::default().with_id(JoystickId::index(id as u32)),
+            VirtualJoystickState {
+                touch_state: Some(touch_state),
+                ..default()
+            },
+            ComputedNode {
+                size: Vec2::splat(300.),
+                ..default()
+            },
+            UiGlobalTransform::default(),
+        ));
+        if component_behaviors {
+            joystick.insert((
+                NoBehavior,
+                JoystickFloating,
+                JoystickDeadZone(0.1),
+                JoystickSmoothing::default(),
+            ));
+        } else {
+            let mut node = joystick.get_mut::<VirtualJoystickNode>().unwrap();
+            node.behavior = std::sync::Arc::new((
+                JoystickFloating,
+                JoystickDeadZone(0.1),
+                JoystickSmoothing::default(),
+            ));
+        }
+        joystick.with_child((
+            VirtualJoystickUIBackground,
+            ComputedNode {
+                size: Vec2::splat(150.),
+                ..default()
+            },
+            UiGlobalTransform::default(),
+        ));
+    }
+    app
+}
+
+/// Average time of a frame of [`Behaviors`].
+fn run(mut app: App) -> Duration {
+    let world = app.world_mut();
+    for _ in 0..10 {
+        world.run_schedule(Behaviors);
+    }
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        world.run_schedule(Behaviors);
+        world
+            .resource_mut::<Messages<VirtualJoystickMessage>>()
+            .clear();
+    }
+    start.elapsed() / FRAMES
+}
+
+fn main() {
+    let trait_objects = run(app(false));
+    let components = run(app(true));
+    println!("{JOYSTICKS} joysticks, average of {FRAMES} frames");
+    println!("  trait objects: {trait_objects:?}");
+    println!("  components:    {components:?}");
+}
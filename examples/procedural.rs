@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
+
+use virtual_joystick::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(EguiPlugin::default())
+        .add_plugins(WorldInspectorPlugin::new())
+        .add_plugins(VirtualJoystickPlugin::<String>::default())
+        .add_systems(Startup, create_scene)
+        .add_systems(Update, update_joystick)
+        .run();
+}
+
+#[derive(Component)]
+/// Player with velocity
+struct Player(pub f32);
+
+fn create_scene(mut cmd: Commands) {
+    cmd.spawn(Camera2d);
+    // Fake Player
+    cmd.spawn((
+        Sprite {
+            color: Color::srgb(0.5, 0.0, 0.5), // Purple
+            custom_size: Some(Vec2::new(50., 50.)),
+            ..default()
+        },
+        Player(50.),
+        Transform::default(),
+    ));
+
+    // Spawn Virtual Joystick drawn without any image assets
+    let joystick = create_procedural_joystick(
+        &mut cmd,
+        "UniqueJoystick".to_string(),
+        ProceduralJoystickStyle {
+            knob_color: Color::srgba(1.0, 0.27, 0.0, 0.8), // OrangeRed
+            outline_color: Color::BLACK,
+            outline_width: 1.,
+            ..default()
+        },
+        None,
+        Vec2::new(75., 75.),
+        Vec2::new(150., 150.),
+        Node {
+            width: Val::Px(150.),
+            height: Val::Px(150.),
+            position_type: PositionType::Absolute,
+            left: Val::Percent(50.),
+            bottom: Val::Percent(15.),
+            ..default()
+        },
+        JoystickFixed,
+        NoAction,
+    );
+
+    // Show where the joystick points even when the knob is covered
+    create_joystick_indicators(&mut cmd, joystick, Color::WHITE, Vec2::new(150., 150.));
+}
+
+fn update_joystick(
+    mut reader: MessageReader<VirtualJoystickMessage<String>>,
+    player: Single<(&mut Transform, &Player)>,
+    time_step: Res<Time>,
+) {
+    let (mut player, player_data) = player.into_inner();
+
+    for joystick in reader.read() {
+        let Vec2 { x, y } = joystick.axis();
+        player.translation.x += x * player_data.0 * time_step.delta_secs();
+        player.translation.y += y * player_data.0 * time_step.delta_secs();
+    }
+}
@@ -0,0 +1,100 @@
+use bevy::{
+    ecs::{
+        component::Component, reflect::ReflectComponent, reflect::ReflectResource,
+        resource::Resource,
+    },
+    prelude::Vec2,
+    reflect::{Reflect, std_traits::ReflectDefault},
+};
+
+/// Insets of the window that should be kept clear of joysticks, in logical pixels.
+///
+/// Set these from platform data (notches, rounded corners, home indicators); they default to zero.
+/// Joysticks with a [`JoystickAnchor`] are placed inside the remaining safe area.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct JoystickSafeArea {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+/// Corner or edge of the safe area a joystick is anchored to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+pub enum JoystickAnchorPoint {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    #[default]
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl JoystickAnchorPoint {
+    /// Position of the anchor inside the safe area, from `(0, 0)` at the top left to `(1, 1)` at
+    /// the bottom right.
+    pub fn alignment(self) -> Vec2 {
+        match self {
+            Self::TopLeft => Vec2::new(0., 0.),
+            Self::Top => Vec2::new(0.5, 0.),
+            Self::TopRight => Vec2::new(1., 0.),
+            Self::Left => Vec2::new(0., 0.5),
+            Self::Center => Vec2::new(0.5, 0.5),
+            Self::Right => Vec2::new(1., 0.5),
+            Self::BottomLeft => Vec2::new(0., 1.),
+            Self::Bottom => Vec2::new(0.5, 1.),
+            Self::BottomRight => Vec2::new(1., 1.),
+        }
+    }
+}
+
+/// Distance between a joystick and the edges of the safe area it is anchored to.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+pub enum JoystickMargin {
+    /// Margin in logical pixels.
+    Px(Vec2),
+    /// Margin in percent of the width and height of the safe area.
+    Percent(Vec2),
+}
+
+impl JoystickMargin {
+    /// Resolve the margin in logical pixels for a safe area of `size`.
+    pub fn resolve(self, size: Vec2) -> Vec2 {
+        match self {
+            Self::Px(margin) => margin,
+            Self::Percent(margin) => margin / 100. * size,
+        }
+    }
+}
+
+impl Default for JoystickMargin {
+    fn default() -> Self {
+        Self::Px(Vec2::splat(20.))
+    }
+}
+
+/// Place a joystick relative to a corner or edge of the [`JoystickSafeArea`] of the primary window.
+///
+/// Replaces the `left`, `right`, `top` and `bottom` of the joystick [`bevy::ui::Node`] and makes it
+/// absolutely positioned, so it should be a root node. The joystick is placed again whenever the
+/// window is resized or rotated, or the safe area changes. On the centered axis of an edge
+/// anchor the margin offsets the joystick from the center.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickAnchor {
+    pub point: JoystickAnchorPoint,
+    pub margin: JoystickMargin,
+}
+
+impl JoystickAnchor {
+    pub fn new(point: JoystickAnchorPoint, margin: JoystickMargin) -> Self {
+        Self { point, margin }
+    }
+}
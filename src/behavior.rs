@@ -1,17 +1,36 @@
 use std::sync::Arc;
 
 use bevy::{
-    ecs::{entity::Entity, world::World},
-    math::{FloatPow, Rect, Vec2},
-    prelude::{Children, Visibility},
-    reflect::Reflect,
-    ui::{ComputedNode, UiGlobalTransform, UiScale},
+    color::Color,
+    ecs::{
+        change_detection::DetectChangesMut, component::Component, entity::Entity,
+        reflect::ReflectComponent, world::World,
+    },
+    math::{
+        BVec2, FloatPow, Mat2, Rect, StableInterpolate, Vec2,
+        curve::{Curve, EaseFunction},
+        ops,
+    },
+    prelude::{ChildOf, Children, Has, Query, Visibility, With},
+    reflect::{Reflect, std_traits::ReflectDefault},
+    time::Time,
+    ui::{BackgroundColor, ComputedNode, UiGlobalTransform, UiScale, widget::ImageNode},
+    window::{PrimaryWindow, Window},
 };
+#[cfg(feature = "inspect")]
+use bevy_inspector_egui::InspectorOptions;
+#[cfg(feature = "inspect")]
+use bevy_inspector_egui::prelude::ReflectInspectorOptions;
 use variadics_please::all_tuples;
 
 use crate::{
-    VirtualJoystickUIBackground,
-    components::{TouchState, VirtualJoystickState},
+    JoystickColors, JoystickVisualStates, VirtualJoystickInteractionArea, VirtualJoystickNode,
+    VirtualJoystickUIBackground, VirtualJoystickUIKnob,
+    components::{
+        InteractionArea, JoystickFadeState, JoystickFadeVisualStates, JoystickHiddenByBehavior,
+        JoystickInteractionRegion, JoystickSmoothedByBehavior, JoystickSmoothingState,
+        JoystickStickyState, TouchState, VirtualJoystickState,
+    },
 };
 
 pub trait VirtualJoystickBehavior: Send + Sync + 'static {
@@ -32,6 +51,18 @@ impl<A: VirtualJoystickBehavior + Clone> VirtualJoystickBehavior for Arc<A> {
     }
 }
 
+impl VirtualJoystickBehavior for Arc<dyn VirtualJoystickBehavior> {
+    fn update_at_delta_stage(&self, world: &mut World, entity: Entity) {
+        (**self).update_at_delta_stage(world, entity);
+    }
+    fn update_at_constraint_stage(&self, world: &mut World, entity: Entity) {
+        (**self).update_at_constraint_stage(world, entity);
+    }
+    fn update(&self, world: &mut World, entity: Entity) {
+        (**self).update(world, entity);
+    }
+}
+
 macro_rules! impl_behavior_sets {
     ($($set: ident),*) => {
         impl<$($set: VirtualJoystickBehavior),*> VirtualJoystickBehavior for ($($set,)*)
@@ -57,32 +88,357 @@ macro_rules! impl_behavior_sets {
 
 all_tuples!(impl_behavior_sets, 1, 20, S);
 
-#[derive(Clone, Copy, Debug, Default, Reflect)]
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct JoystickDeadZone(pub f32);
 
-#[derive(Clone, Copy, Debug, Default, Reflect)]
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct JoystickHorizontalOnly;
 
-#[derive(Clone, Copy, Debug, Default, Reflect)]
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct JoystickVerticalOnly;
 
-#[derive(Clone, Copy, Debug, Default, Reflect)]
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct JoystickInvisible;
 
-#[derive(Clone, Copy, Debug, Default, Reflect)]
+/// Fading alternative to [`JoystickInvisible`].
+///
+/// Fades the joystick in on press and out after release, optionally staying faintly visible
+/// while untouched so players can discover it. Durations are in seconds.
+/// The opacity is kept in [`JoystickFadeState`] and applied through [`crate::JoystickVisualStates`],
+/// which is inserted from the current colors if missing. It fades every color of the joystick,
+/// including borders, outlines, indicators and the color of its interaction area.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[require(JoystickFadeState)]
+pub struct JoystickFade {
+    pub fade_in: f32,
+    pub fade_out: f32,
+    /// Time the joystick stays fully visible after release before fading out.
+    pub linger: f32,
+    /// Opacity while untouched, the joystick is hidden if this is zero.
+    pub idle_opacity: f32,
+}
+
+impl Default for JoystickFade {
+    fn default() -> Self {
+        Self {
+            fade_in: 0.1,
+            fade_out: 0.3,
+            linger: 0.5,
+            idle_opacity: 0.,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct JoystickFixed;
 
-#[derive(Clone, Copy, Debug, Default, Reflect)]
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct JoystickFloating;
 
-#[derive(Clone, Copy, Debug, Default, Reflect)]
+/// Floating joystick whose base follows the pointer once it is dragged past the rim.
+///
+/// The base is kept inside its [`JoystickBaseBounds`], the primary window by default. How it
+/// follows is configured with an optional [`JoystickDynamicFollow`].
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[require(JoystickBaseBounds)]
 pub struct JoystickDynamic;
 
-impl VirtualJoystickBehavior for JoystickDeadZone {
+/// How the base of a [`JoystickDynamic`] joystick follows the pointer.
+///
+/// Without this component the base follows like [`Self::default`], use [`Self::RIGID`] to drag
+/// it along rigidly instead.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickDynamicFollow {
+    /// Time in seconds the base takes to halve its distance to the pointer past the rim, the base
+    /// is dragged along rigidly if this is zero.
+    pub follow_half_life: f32,
+    /// Maximum distance in logical pixels the base follows away from where it was pressed.
+    pub max_distance: Option<f32>,
+    /// Move the base back home on release instead of leaving it where it was dragged to.
+    pub return_home: bool,
+}
+
+impl JoystickDynamicFollow {
+    /// The base keeps the pointer on its rim and returns home on release.
+    pub const RIGID: Self = Self {
+        follow_half_life: 0.,
+        max_distance: None,
+        return_home: true,
+    };
+}
+
+impl Default for JoystickDynamicFollow {
+    /// A slightly eased follow that returns home on release.
+    fn default() -> Self {
+        Self {
+            follow_half_life: 0.04,
+            ..Self::RIGID
+        }
+    }
+}
+
+/// Region the base of a [`JoystickFloating`] or [`JoystickDynamic`] joystick may not leave.
+///
+/// The base is placed on the press and then clamped to stay fully inside the region, the delta is
+/// relative to the clamped base. Floating joysticks are unbounded unless this is inserted, while
+/// dynamic joysticks are kept inside the primary window by default.
+///
+/// The interaction area of joysticks spawned with [`crate::create_joystick`] is only as large as
+/// the joystick, so [`Self::InteractionArea`] is best paired with a larger
+/// [`crate::JoystickInteractionRegion`].
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+pub enum JoystickBaseBounds {
+    /// The base may leave the joystick, even the screen.
+    Unbounded,
+    /// The [`crate::JoystickInteractionRegion`] or [`crate::VirtualJoystickInteractionArea`] of the
+    /// joystick, or the joystick node itself.
+    InteractionArea,
+    /// The parent node of the joystick.
+    Parent,
+    /// The primary window.
+    #[default]
+    Window,
+}
+
+/// Fixed joystick that keeps its delta after release, like a throttle lever.
+///
+/// Only the axes enabled in [`Self::axes`] hold their value, the others return to zero.
+/// The held delta is kept in [`crate::JoystickStickyState`] and stays in
+/// [`VirtualJoystickState::delta`] while released, though no messages are sent until the next press.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[require(JoystickStickyState)]
+pub struct JoystickSticky {
+    /// Axes that keep their value after release.
+    pub axes: BVec2,
+    /// Move the knob relative to its held position on the next press instead of jumping to the pointer.
+    pub relative_regrab: bool,
+    /// Values a held axis snaps to when released within [`Self::detent_radius`] of them.
+    pub detents: Vec<f32>,
+    pub detent_radius: f32,
+}
+
+impl Default for JoystickSticky {
+    fn default() -> Self {
+        Self {
+            axes: BVec2::TRUE,
+            relative_regrab: false,
+            detents: vec![0.],
+            detent_radius: 0.1,
+        }
+    }
+}
+
+impl JoystickSticky {
+    /// Sticky joystick holding only the given axes.
+    pub fn axes(axes: BVec2) -> Self {
+        Self {
+            axes,
+            ..Default::default()
+        }
+    }
+
+    /// Snap `value` to the nearest detent within [`Self::detent_radius`].
+    fn snap(&self, value: f32) -> f32 {
+        self.detents
+            .iter()
+            .copied()
+            .filter(|detent| (value - detent).abs() <= self.detent_radius)
+            .min_by(|a, b| (value - a).abs().total_cmp(&(value - b).abs()))
+            .unwrap_or(value)
+    }
+}
+
+/// Filters [`VirtualJoystickState::delta`] while dragging and animates the knob and base back
+/// home after release instead of snapping them, or to the delta held by [`JoystickSticky`].
+///
+/// Place it after the behaviors it should smooth, e.g. `(JoystickFloating, JoystickSmoothing::default())`.
+/// The smoothed values are kept in [`JoystickSmoothingState`].
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[require(JoystickSmoothingState)]
+pub struct JoystickSmoothing {
+    pub filter: JoystickSmoothingFilter,
+    /// Time in seconds the knob and base take to return after release.
+    pub return_duration: f32,
+    /// Easing used while returning home.
+    pub return_ease: EaseFunction,
+    /// Replace [`VirtualJoystickState::delta`] with the smoothed delta so it is also reflected
+    /// in [`crate::VirtualJoystickMessage`]s.
+    pub smooth_output: bool,
+}
+
+impl Default for JoystickSmoothing {
+    fn default() -> Self {
+        Self {
+            filter: JoystickSmoothingFilter::default(),
+            return_duration: 0.15,
+            return_ease: EaseFunction::CubicOut,
+            smooth_output: false,
+        }
+    }
+}
+
+/// Filter applied by [`JoystickSmoothing`] while dragging.
+#[derive(Clone, Copy, Debug, Reflect)]
+pub enum JoystickSmoothingFilter {
+    /// Exponential decay towards the raw delta, halving the remaining distance every `half_life` seconds.
+    Exponential { half_life: f32 },
+    /// Critically damped spring reaching the raw delta in roughly `smooth_time` seconds without overshoot.
+    CriticallyDamped { smooth_time: f32 },
+}
+
+impl Default for JoystickSmoothingFilter {
+    fn default() -> Self {
+        Self::Exponential { half_life: 0.03 }
+    }
+}
+
+/// Any of the built-in behaviors as a reflectable value, see [`JoystickBehaviors`].
+#[derive(Clone, Debug, Default, Reflect)]
+#[reflect(Default)]
+pub enum JoystickBehavior {
+    DeadZone(JoystickDeadZone),
+    HorizontalOnly,
+    VerticalOnly,
+    Invisible,
+    Fade(JoystickFade),
+    Fixed,
+    #[default]
+    Floating,
+    Dynamic,
+    Sticky(JoystickSticky),
+    Smoothing(JoystickSmoothing),
+}
+
+impl JoystickBehavior {
+    /// The behavior as a trait object.
+    pub(crate) fn as_dyn(&self) -> &dyn VirtualJoystickBehavior {
+        match self {
+            Self::DeadZone(behavior) => behavior,
+            Self::HorizontalOnly => &JoystickHorizontalOnly,
+            Self::VerticalOnly => &JoystickVerticalOnly,
+            Self::Invisible => &JoystickInvisible,
+            Self::Fade(behavior) => behavior,
+            Self::Fixed => &JoystickFixed,
+            Self::Floating => &JoystickFloating,
+            Self::Dynamic => &JoystickDynamic,
+            Self::Sticky(behavior) => behavior,
+            Self::Smoothing(behavior) => behavior,
+        }
+    }
+}
+
+impl VirtualJoystickBehavior for JoystickBehavior {
+    fn update_at_delta_stage(&self, world: &mut World, entity: Entity) {
+        self.as_dyn().update_at_delta_stage(world, entity);
+    }
     fn update_at_constraint_stage(&self, world: &mut World, entity: Entity) {
-        let Some(mut joystick_state) = world.get_mut::<VirtualJoystickState>(entity) else {
-            return;
-        };
+        self.as_dyn().update_at_constraint_stage(world, entity);
+    }
+    fn update(&self, world: &mut World, entity: Entity) {
+        self.as_dyn().update(world, entity);
+    }
+}
+
+macro_rules! impl_behavior_from {
+    ($($behavior: ident => $variant: ident),*) => {
+        $(impl From<$behavior> for JoystickBehavior {
+            fn from(_: $behavior) -> Self {
+                Self::$variant
+            }
+        })*
+    };
+    ($($behavior: ident => $variant: ident(_)),*) => {
+        $(impl From<$behavior> for JoystickBehavior {
+            fn from(behavior: $behavior) -> Self {
+                Self::$variant(behavior)
+            }
+        })*
+    };
+}
+
+impl_behavior_from!(
+    JoystickHorizontalOnly => HorizontalOnly,
+    JoystickVerticalOnly => VerticalOnly,
+    JoystickInvisible => Invisible,
+    JoystickFixed => Fixed,
+    JoystickFloating => Floating,
+    JoystickDynamic => Dynamic
+);
+impl_behavior_from!(
+    JoystickDeadZone => DeadZone(_),
+    JoystickFade => Fade(_),
+    JoystickSticky => Sticky(_),
+    JoystickSmoothing => Smoothing(_)
+);
+
+/// Reflectable stack of built-in behaviors, shown and editable in inspectors and saved in scenes.
+///
+/// The behaviors run in order after [`crate::VirtualJoystickNode::behavior`], so pair it with
+/// [`NoBehavior`] to only run the stack.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "inspect", derive(InspectorOptions))]
+#[cfg_attr(feature = "inspect", reflect(InspectorOptions))]
+pub struct JoystickBehaviors(pub Vec<JoystickBehavior>);
+
+impl JoystickBehaviors {
+    /// Add `behavior` at the end of the stack.
+    pub fn with(mut self, behavior: impl Into<JoystickBehavior>) -> Self {
+        self.0.push(behavior.into());
+        self
+    }
+}
+
+impl VirtualJoystickBehavior for JoystickBehaviors {
+    fn update_at_delta_stage(&self, world: &mut World, entity: Entity) {
+        for behavior in &self.0 {
+            behavior.update_at_delta_stage(world, entity);
+        }
+    }
+    fn update_at_constraint_stage(&self, world: &mut World, entity: Entity) {
+        for behavior in &self.0 {
+            behavior.update_at_constraint_stage(world, entity);
+        }
+    }
+    fn update(&self, world: &mut World, entity: Entity) {
+        for behavior in &self.0 {
+            behavior.update(world, entity);
+        }
+    }
+}
+
+/// Behavior doing nothing, for joysticks driven only by behavior components.
+///
+/// As a component it also opts the joystick out of the [`crate::VirtualJoystickNode::behavior`]
+/// trait object, so the exclusive behavior systems don't need to run for it.
+///
+/// Behavior components have no order of their own:
+/// - Only one positioning component drives a joystick, [`JoystickSticky`] over
+///   [`JoystickDynamic`] over [`JoystickFloating`] over [`JoystickFixed`].
+/// - Constraints always apply as [`JoystickDeadZone`], then [`JoystickHorizontalOnly`] and
+///   [`JoystickVerticalOnly`], then [`JoystickSmoothing`].
+/// - Trait objects and [`JoystickBehaviors`] run after the components of each stage, in the
+///   order they are composed. Use them for any other order.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct NoBehavior;
+
+impl VirtualJoystickBehavior for NoBehavior {}
+
+impl JoystickDeadZone {
+    pub(crate) fn constrain(&self, joystick_state: &mut VirtualJoystickState) {
         let dead_zone = self.0;
         if joystick_state.delta.x.abs() < dead_zone {
             joystick_state.delta.x = 0.0;
@@ -93,6 +449,15 @@ impl VirtualJoystickBehavior for JoystickDeadZone {
     }
 }
 
+impl VirtualJoystickBehavior for JoystickDeadZone {
+    fn update_at_constraint_stage(&self, world: &mut World, entity: Entity) {
+        let Some(mut joystick_state) = world.get_mut::<VirtualJoystickState>(entity) else {
+            return;
+        };
+        self.constrain(&mut joystick_state);
+    }
+}
+
 impl VirtualJoystickBehavior for JoystickHorizontalOnly {
     fn update_at_constraint_stage(&self, world: &mut World, entity: Entity) {
         let Some(mut joystick_state) = world.get_mut::<VirtualJoystickState>(entity) else {
@@ -111,15 +476,12 @@ impl VirtualJoystickBehavior for JoystickVerticalOnly {
     }
 }
 
-impl VirtualJoystickBehavior for JoystickInvisible {
-    fn update(&self, world: &mut World, entity: Entity) {
-        let joystick_state = world.get::<VirtualJoystickState>(entity).cloned();
-        let Some(joystick_state) = joystick_state else {
-            return;
-        };
-        let Some(mut joystick_visibility) = world.get_mut::<Visibility>(entity) else {
-            return;
-        };
+impl JoystickInvisible {
+    pub(crate) fn update_visibility(
+        &self,
+        joystick_state: &VirtualJoystickState,
+        joystick_visibility: &mut Visibility,
+    ) {
         if joystick_state.just_released
             || *joystick_visibility != Visibility::Hidden && joystick_state.touch_state.is_none()
         {
@@ -133,15 +495,141 @@ impl VirtualJoystickBehavior for JoystickInvisible {
     }
 }
 
-impl VirtualJoystickBehavior for JoystickFixed {
-    fn update_at_delta_stage(&self, world: &mut World, entity: Entity) {
-        let Some(joystick_base_rect) = joystick_base_rect(&*world, entity) else {
+impl VirtualJoystickBehavior for JoystickInvisible {
+    fn update(&self, world: &mut World, entity: Entity) {
+        let joystick_state = world.get::<VirtualJoystickState>(entity).cloned();
+        let Some(joystick_state) = joystick_state else {
             return;
         };
-        let Some(mut joystick_state) = world.get_mut::<VirtualJoystickState>(entity) else {
+        let mut entity_mut = world.entity_mut(entity);
+        let Some(mut joystick_visibility) = entity_mut.get_mut::<Visibility>() else {
+            return;
+        };
+        let previous = *joystick_visibility;
+        self.update_visibility(&joystick_state, &mut joystick_visibility);
+        let visibility = *joystick_visibility;
+        if visibility != previous {
+            JoystickHiddenByBehavior::track(&mut entity_mut, visibility);
+        }
+    }
+}
+
+impl JoystickFade {
+    /// Advance `fade_state` by `delta_secs` and return the matching [`Visibility`].
+    pub(crate) fn fade(
+        &self,
+        touched: bool,
+        fade_state: &mut JoystickFadeState,
+        delta_secs: f32,
+    ) -> Visibility {
+        if touched {
+            fade_state.linger = 0.;
+            fade_state.opacity += delta_secs / self.fade_in.max(f32::EPSILON);
+        } else if fade_state.linger < self.linger {
+            fade_state.linger += delta_secs;
+        } else {
+            fade_state.opacity -= delta_secs / self.fade_out.max(f32::EPSILON);
+        }
+        let min_opacity = if touched { 0. } else { self.idle_opacity };
+        fade_state.opacity = fade_state.opacity.clamp(min_opacity.min(1.), 1.);
+
+        // Hide the joystick completely once faded out.
+        if fade_state.opacity > 0. {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    }
+}
+
+impl VirtualJoystickBehavior for JoystickFade {
+    fn update(&self, world: &mut World, entity: Entity) {
+        let delta_secs = world.resource::<Time>().delta_secs();
+        let Some(joystick_state) = world.get::<VirtualJoystickState>(entity) else {
             return;
         };
+        let touched = joystick_state.touch_state.is_some();
+
+        if world.get::<JoystickVisualStates>(entity).is_none() {
+            let mut part_query = world.query::<PartColorQuery>();
+            let children = world
+                .get::<Children>(entity)
+                .map_or(&[][..], |children| children);
+            let visual_states = current_visual_states(children, &part_query.query(world));
+            world
+                .entity_mut(entity)
+                .insert((visual_states, JoystickFadeVisualStates));
+        }
+        let mut fade_state =
+            world
+                .get::<JoystickFadeState>(entity)
+                .copied()
+                .unwrap_or(JoystickFadeState {
+                    opacity: self.idle_opacity,
+                    linger: self.linger,
+                });
+
+        let visibility = self.fade(touched, &mut fade_state, delta_secs);
+        let mut entity_mut = world.entity_mut(entity);
+        if let Some(mut joystick_visibility) = entity_mut.get_mut::<Visibility>()
+            && *joystick_visibility != visibility
+        {
+            *joystick_visibility = visibility;
+            JoystickHiddenByBehavior::track(&mut entity_mut, visibility);
+        }
+        entity_mut.insert(fade_state);
+    }
+}
+
+/// Kind and color of a joystick part, see [`current_visual_states`].
+pub(crate) type PartColorQuery = (
+    Has<VirtualJoystickUIKnob>,
+    Has<VirtualJoystickUIBackground>,
+    Option<&'static ImageNode>,
+    Option<&'static BackgroundColor>,
+);
+
+/// [`JoystickVisualStates`] keeping the current colors of the parts among `children` in every
+/// state but [`crate::JoystickVisualState::Disabled`], which tints them like the default
+/// [`JoystickColors::disabled`].
+pub(crate) fn current_visual_states(
+    children: &[Entity],
+    part_query: &Query<PartColorQuery>,
+) -> JoystickVisualStates {
+    let (mut knob, mut background) = (Color::WHITE, Color::WHITE);
+    for &child in children {
+        let Ok((is_knob, is_background, image_node, background_color)) = part_query.get(child)
+        else {
+            continue;
+        };
+        let Some(color) = image_node
+            .map(|image_node| image_node.color)
+            .or_else(|| background_color.map(|color| color.0))
+        else {
+            continue;
+        };
+        if is_knob {
+            knob = color;
+        } else if is_background {
+            background = color;
+        }
+    }
 
+    let mut visual_states = JoystickVisualStates::from_colors(knob, background);
+    visual_states.pressed = visual_states.idle;
+    visual_states.dragging = visual_states.idle;
+    visual_states.disabled = visual_states.idle;
+    visual_states.disabled.knob.color = JoystickColors::default_disabled(knob);
+    visual_states.disabled.background.color = JoystickColors::default_disabled(background);
+    visual_states
+}
+
+impl JoystickFixed {
+    pub(crate) fn knob_delta(
+        &self,
+        joystick_state: &mut VirtualJoystickState,
+        joystick_base_frame: NodeFrame,
+    ) {
         joystick_state.base_offset = Vec2::ZERO;
 
         // Return if `touch_state` is `None` and set delta to `ZERO`.
@@ -151,52 +639,127 @@ impl VirtualJoystickBehavior for JoystickFixed {
         };
 
         // Set `joystick_state.delta`.
-        let offset = touch_state.current - joystick_base_rect.center();
-        joystick_state.delta = joystick_delta(joystick_base_rect, offset, false);
+        let offset =
+            joystick_base_frame.to_local(touch_state.current - joystick_base_frame.center());
+        joystick_state.delta = joystick_delta(joystick_base_frame.rect, offset, false);
     }
 }
 
-impl VirtualJoystickBehavior for JoystickDynamic {
+impl VirtualJoystickBehavior for JoystickFixed {
     fn update_at_delta_stage(&self, world: &mut World, entity: Entity) {
-        let Some(joystick_rect) = joystick_rect(world, entity) else {
-            return;
-        };
-        let Some(joystick_base_rect) = joystick_base_rect(&*world, entity) else {
+        let Some(joystick_base_frame) = joystick_base_frame(&*world, entity) else {
             return;
         };
         let Some(mut joystick_state) = world.get_mut::<VirtualJoystickState>(entity) else {
             return;
         };
+        self.knob_delta(&mut joystick_state, joystick_base_frame);
+    }
+}
 
+impl JoystickDynamic {
+    pub(crate) fn knob_delta(
+        &self,
+        joystick_state: &mut VirtualJoystickState,
+        joystick_frame: NodeFrame,
+        joystick_base_frame: NodeFrame,
+        bounds: Option<NodeFrame>,
+        follow: &JoystickDynamicFollow,
+        delta_secs: f32,
+    ) {
         // Return if `touch_state` is `None` and set delta to `ZERO`.
-        let Some(touch_state) = update_base_offset(&mut joystick_state, joystick_base_rect) else {
+        let Some(touch_state) = &joystick_state.touch_state else {
+            if joystick_state.just_released && follow.return_home {
+                joystick_state.base_offset = Vec2::ZERO;
+            }
             joystick_state.delta = Vec2::ZERO;
             return;
         };
 
-        // Set `joystick_state.delta`.
-        let offset = touch_state.current
-            - (joystick_rect.min + joystick_state.base_offset + joystick_base_rect.half_size());
-        joystick_state.delta = joystick_delta(joystick_base_rect, offset, false);
+        // NOTE: Offsets are calculated in the local space of the joystick, relative to the
+        //       top left corner where `joystick_state.base_offset` starts.
+        let base_half_size = joystick_base_frame.rect.half_size();
+        let clamp = |base_offset: Vec2| {
+            clamp_base_offset(base_offset, base_half_size, joystick_frame, bounds)
+        };
 
-        // Calculate appropriate `delta` and add to `joystick_state.base_offset` if appropriate.
-        if let Some(delta) = base_offset_delta(joystick_base_rect, offset) {
-            joystick_state.base_offset += delta;
+        // Center the base on the press, then follow the pointer past the rim.
+        let pressed_offset = clamp(joystick_frame.local_point(touch_state.start) - base_half_size);
+        let mut base_offset = if touch_state.just_pressed {
+            pressed_offset
+        } else {
+            joystick_state.base_offset
+        };
+        let current = joystick_frame.local_point(touch_state.current);
+        if let Some(delta) = base_offset_delta(
+            joystick_base_frame.rect,
+            current - (base_offset + base_half_size),
+        ) {
+            let amount = if follow.follow_half_life > 0. {
+                1. - ops::exp2(-delta_secs / follow.follow_half_life)
+            } else {
+                1.
+            };
+            base_offset += delta * amount;
+        }
+        // NOTE: `max_distance` is in logical pixels, so it is applied in screen space.
+        if let Some(max_distance) = follow.max_distance {
+            let distance = joystick_frame.to_screen(base_offset - pressed_offset);
+            base_offset =
+                pressed_offset + joystick_frame.to_local(distance.clamp_length_max(max_distance));
         }
+        joystick_state.base_offset = clamp(base_offset);
+
+        // Set `joystick_state.delta` relative to the moved base.
+        let offset = current - (joystick_state.base_offset + base_half_size);
+        joystick_state.delta = joystick_delta(joystick_base_frame.rect, offset, false);
     }
 }
 
-impl VirtualJoystickBehavior for JoystickFloating {
+impl VirtualJoystickBehavior for JoystickDynamic {
     fn update_at_delta_stage(&self, world: &mut World, entity: Entity) {
-        let Some(joystick_base_rect) = joystick_base_rect(&*world, entity) else {
+        let Some(joystick_frame) = joystick_frame(world, entity) else {
+            return;
+        };
+        let Some(joystick_base_frame) = joystick_base_frame(&*world, entity) else {
             return;
         };
+        let bounds = world
+            .get::<JoystickBaseBounds>(entity)
+            .copied()
+            .unwrap_or_default();
+        let bounds = bounds_frame(world, entity, bounds);
+        let follow = world
+            .get::<JoystickDynamicFollow>(entity)
+            .copied()
+            .unwrap_or_default();
+        let delta_secs = world.resource::<Time>().delta_secs();
         let Some(mut joystick_state) = world.get_mut::<VirtualJoystickState>(entity) else {
             return;
         };
+        self.knob_delta(
+            &mut joystick_state,
+            joystick_frame,
+            joystick_base_frame,
+            bounds,
+            &follow,
+            delta_secs,
+        );
+    }
+}
 
+impl JoystickFloating {
+    pub(crate) fn knob_delta(
+        &self,
+        joystick_state: &mut VirtualJoystickState,
+        joystick_frame: NodeFrame,
+        joystick_base_frame: NodeFrame,
+        bounds: Option<NodeFrame>,
+    ) {
         // Return if `touch_state` is `None` or `touch_state.just_pressed` and set delta to `ZERO`.
-        let Some(touch_state) = update_base_offset(&mut joystick_state, joystick_base_rect) else {
+        let Some(touch_state) =
+            update_base_offset(joystick_state, joystick_frame, joystick_base_frame, bounds)
+        else {
             joystick_state.delta = Vec2::ZERO;
             return;
         };
@@ -205,36 +768,400 @@ impl VirtualJoystickBehavior for JoystickFloating {
             return;
         }
 
-        // Set `joystick_state.delta`.
-        let offset = touch_state.current - joystick_base_rect.center();
-        joystick_state.delta = joystick_delta(joystick_base_rect, offset, true);
+        // Set `joystick_state.delta` relative to the placed base.
+        let offset = joystick_frame.local_point(touch_state.current)
+            - (joystick_state.base_offset + joystick_base_frame.rect.half_size());
+        joystick_state.delta = joystick_delta(joystick_base_frame.rect, offset, true);
+    }
+}
+
+impl VirtualJoystickBehavior for JoystickFloating {
+    fn update_at_delta_stage(&self, world: &mut World, entity: Entity) {
+        let Some(joystick_frame) = joystick_frame(world, entity) else {
+            return;
+        };
+        let Some(joystick_base_frame) = joystick_base_frame(&*world, entity) else {
+            return;
+        };
+        let bounds = world
+            .get::<JoystickBaseBounds>(entity)
+            .copied()
+            .unwrap_or(JoystickBaseBounds::Unbounded);
+        let bounds = bounds_frame(world, entity, bounds);
+        let Some(mut joystick_state) = world.get_mut::<VirtualJoystickState>(entity) else {
+            return;
+        };
+        self.knob_delta(
+            &mut joystick_state,
+            joystick_frame,
+            joystick_base_frame,
+            bounds,
+        );
+    }
+}
+
+impl JoystickSticky {
+    pub(crate) fn knob_delta(
+        &self,
+        joystick_state: &mut VirtualJoystickState,
+        sticky_state: &mut JoystickStickyState,
+        joystick_base_frame: NodeFrame,
+    ) {
+        joystick_state.base_offset = Vec2::ZERO;
+
+        if let Some(touch_state) = &joystick_state.touch_state {
+            let offset =
+                joystick_base_frame.to_local(touch_state.current - joystick_base_frame.center());
+            let pointer_delta = joystick_delta(joystick_base_frame.rect, offset, false);
+
+            // Remember where the pointer grabbed the held knob when re-grabbing relatively.
+            if touch_state.just_pressed {
+                sticky_state.grab_offset = self
+                    .relative_regrab
+                    .then(|| sticky_state.held - pointer_delta);
+            }
+            joystick_state.delta = match sticky_state.grab_offset {
+                Some(grab_offset) => (pointer_delta + grab_offset).clamp(Vec2::NEG_ONE, Vec2::ONE),
+                None => pointer_delta,
+            };
+        } else {
+            self.hold(joystick_state, sticky_state);
+        }
+    }
+
+    /// Hold the released delta on sticky axes, snapped to the nearest detent.
+    fn hold(
+        &self,
+        joystick_state: &mut VirtualJoystickState,
+        sticky_state: &mut JoystickStickyState,
+    ) {
+        if joystick_state.just_released {
+            let Vec2 { x, y } = joystick_state.delta;
+            sticky_state.held = Vec2::new(
+                if self.axes.x { self.snap(x) } else { 0. },
+                if self.axes.y { self.snap(y) } else { 0. },
+            );
+            sticky_state.grab_offset = None;
+        }
+        joystick_state.delta = sticky_state.held;
     }
 }
 
-/// The [`Rect`] of the joystick returned as an [`Option`].
-fn joystick_rect(world: &World, entity: Entity) -> Option<Rect> {
+impl VirtualJoystickBehavior for JoystickSticky {
+    fn update_at_delta_stage(&self, world: &mut World, entity: Entity) {
+        let Some(joystick_base_frame) = joystick_base_frame(&*world, entity) else {
+            return;
+        };
+        let mut entity_mut = world.entity_mut(entity);
+        if !entity_mut.contains::<JoystickStickyState>() {
+            entity_mut.insert(JoystickStickyState::default());
+        }
+        let Ok((mut joystick_state, mut sticky_state)) = entity_mut
+            .get_components_mut::<(&mut VirtualJoystickState, &mut JoystickStickyState)>()
+        else {
+            return;
+        };
+
+        // Only mark the sticky state changed if the held delta or grab changes.
+        let previous = sticky_state.clone();
+        self.knob_delta(
+            &mut joystick_state,
+            sticky_state.bypass_change_detection(),
+            joystick_base_frame,
+        );
+        if *sticky_state != previous {
+            sticky_state.set_changed();
+        }
+    }
+}
+
+impl JoystickSmoothing {
+    pub(crate) fn constrain(
+        &self,
+        joystick_state: &mut VirtualJoystickState,
+        smoothed: &mut JoystickSmoothingState,
+        delta_secs: f32,
+    ) {
+        if joystick_state.touch_state.is_some() {
+            smoothed.release = None;
+            smoothed.base_offset = joystick_state.base_offset;
+            let target = joystick_state.delta;
+            match self.filter {
+                JoystickSmoothingFilter::Exponential { half_life } => {
+                    let decay_rate = std::f32::consts::LN_2 / half_life.max(f32::EPSILON);
+                    smoothed.delta.smooth_nudge(&target, decay_rate, delta_secs);
+                }
+                JoystickSmoothingFilter::CriticallyDamped { smooth_time } => {
+                    let (delta, velocity) = critically_damped(
+                        smoothed.delta,
+                        smoothed.velocity,
+                        target,
+                        smooth_time,
+                        delta_secs,
+                    );
+                    smoothed.delta = delta;
+                    smoothed.velocity = velocity;
+                }
+            }
+        } else {
+            if joystick_state.just_released {
+                smoothed.release = Some((smoothed.delta, smoothed.base_offset, 0.));
+                smoothed.velocity = Vec2::ZERO;
+            }
+            // Animate to where the knob and base rest over `return_duration`, starting from the
+            // values at release. They rest home unless held, e.g. by `JoystickSticky`.
+            match &mut smoothed.release {
+                Some((delta, base_offset, elapsed)) => {
+                    *elapsed += delta_secs;
+                    let t = *elapsed / self.return_duration.max(f32::EPSILON);
+                    let progress = self.return_ease.sample_clamped(t);
+                    let (delta, base_offset) = (
+                        delta.lerp(joystick_state.delta, progress),
+                        base_offset.lerp(joystick_state.base_offset, progress),
+                    );
+                    if t >= 1. {
+                        smoothed.release = None;
+                    }
+                    smoothed.delta = delta;
+                    smoothed.base_offset = base_offset;
+                }
+                None => {
+                    smoothed.delta = joystick_state.delta;
+                    smoothed.base_offset = joystick_state.base_offset;
+                }
+            }
+        }
+
+        if self.smooth_output && joystick_state.touch_state.is_some() {
+            joystick_state.delta = smoothed.delta;
+        }
+    }
+}
+
+impl VirtualJoystickBehavior for JoystickSmoothing {
+    fn update_at_constraint_stage(&self, world: &mut World, entity: Entity) {
+        let delta_secs = world.resource::<Time>().delta_secs();
+        let mut entity_mut = world.entity_mut(entity);
+        if !entity_mut.contains::<JoystickSmoothingState>() {
+            entity_mut.insert((
+                JoystickSmoothingState::default(),
+                JoystickSmoothedByBehavior,
+            ));
+        }
+        let Ok((mut joystick_state, mut smoothed)) = entity_mut
+            .get_components_mut::<(&mut VirtualJoystickState, &mut JoystickSmoothingState)>()
+        else {
+            return;
+        };
+
+        // Only mark the state changed if the delta is smoothed.
+        let delta = joystick_state.delta;
+        self.constrain(
+            joystick_state.bypass_change_detection(),
+            &mut smoothed,
+            delta_secs,
+        );
+        if joystick_state.delta != delta {
+            joystick_state.set_changed();
+        }
+    }
+}
+
+/// Step a critically damped spring from `current` with `velocity` towards `target`.
+fn critically_damped(
+    current: Vec2,
+    velocity: Vec2,
+    target: Vec2,
+    smooth_time: f32,
+    delta_secs: f32,
+) -> (Vec2, Vec2) {
+    let omega = 2. / smooth_time.max(f32::EPSILON);
+    let offset = current - target;
+    let impulse = velocity + offset * omega;
+    let decay = ops::exp(-omega * delta_secs);
+    (
+        target + (offset + impulse * delta_secs) * decay,
+        (velocity - impulse * omega * delta_secs) * decay,
+    )
+}
+
+/// Placement of a joystick node on screen, honoring the rotation and scale of its [`UiGlobalTransform`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct NodeFrame {
+    /// Rect centered on the node on screen with the unrotated, unscaled size of the node.
+    rect: Rect,
+    /// Rotation and scale of the node.
+    matrix: Mat2,
+}
+
+impl NodeFrame {
+    pub(crate) fn new(node: &ComputedNode, transform: &UiGlobalTransform, ui_scale: f32) -> Self {
+        let factor = node.inverse_scale_factor * ui_scale;
+        Self {
+            rect: Rect::from_center_size(transform.translation * factor, node.size() * factor),
+            matrix: transform.matrix2,
+        }
+    }
+
+    /// Frame of an unrotated, unscaled `rect` on screen.
+    pub(crate) fn from_rect(rect: Rect) -> Self {
+        Self {
+            rect,
+            matrix: Mat2::IDENTITY,
+        }
+    }
+
+    pub(crate) fn center(self) -> Vec2 {
+        self.rect.center()
+    }
+
+    /// Bring a point on screen into the local space of the node, relative to its top left corner.
+    fn local_point(self, point: Vec2) -> Vec2 {
+        self.to_local(point - self.center()) + self.rect.half_size()
+    }
+
+    /// Bring a point in the local space of the node, relative to its top left corner, on screen.
+    fn screen_point(self, point: Vec2) -> Vec2 {
+        self.center() + self.to_screen(point - self.rect.half_size())
+    }
+
+    /// Bring an offset from screen space into the local space of the node.
+    fn to_local(self, offset: Vec2) -> Vec2 {
+        if self.matrix.determinant().abs() <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+        self.matrix.inverse() * offset
+    }
+
+    /// Bring an offset from the local space of the node into screen space.
+    fn to_screen(self, offset: Vec2) -> Vec2 {
+        self.matrix * offset
+    }
+}
+
+/// Let the behaviors of the joystick take a release outside of the joystick systems into
+/// account, e.g. to hold the released delta of [`JoystickSticky`] or ease the knob of
+/// [`JoystickSmoothing`] home.
+///
+/// Expects [`VirtualJoystickState::just_released`] to be set, see
+/// [`crate::systems::release_joystick`].
+pub(crate) fn release_behavior_state(world: &mut World, entity: Entity) {
+    let behavior = world
+        .get::<VirtualJoystickNode>(entity)
+        .map(|node| Arc::clone(&node.behavior));
+    if let Some(behavior) = behavior {
+        behavior.update_at_delta_stage(world, entity);
+        behavior.update_at_constraint_stage(world, entity);
+    }
+
+    let mut entity_mut = world.entity_mut(entity);
+    if let Some(sticky) = entity_mut.get::<JoystickSticky>().cloned()
+        && let Ok((mut joystick_state, mut sticky_state)) =
+            entity_mut.get_components_mut::<(&mut VirtualJoystickState, &mut JoystickStickyState)>()
+    {
+        sticky.hold(&mut joystick_state, &mut sticky_state);
+    }
+    if let Some(smoothing) = entity_mut.get::<JoystickSmoothing>().copied()
+        && let Ok((mut joystick_state, mut smoothed)) = entity_mut
+            .get_components_mut::<(&mut VirtualJoystickState, &mut JoystickSmoothingState)>()
+    {
+        smoothing.constrain(&mut joystick_state, &mut smoothed, 0.);
+    }
+}
+
+/// The [`NodeFrame`] of the joystick returned as an [`Option`].
+fn joystick_frame(world: &World, entity: Entity) -> Option<NodeFrame> {
     let ui_scale = world.get_resource::<UiScale>()?;
     let node = world.get::<ComputedNode>(entity)?;
     let transform = world.get::<UiGlobalTransform>(entity)?;
-    let factor = node.inverse_scale_factor * ui_scale.0;
-
-    Some(Rect::from_center_size(
-        transform.translation * factor,
-        node.size() * factor,
-    ))
+    Some(NodeFrame::new(node, transform, ui_scale.0))
 }
 
-/// The [`Rect`] of the joystick base returned as an [`Option`].
-fn joystick_base_rect(world: &World, entity: Entity) -> Option<Rect> {
+/// The [`NodeFrame`] of the joystick base returned as an [`Option`].
+fn joystick_base_frame(world: &World, entity: Entity) -> Option<NodeFrame> {
     let children = world.get::<Children>(entity)?;
     let base = children
         .iter()
         .find(|entity| world.get::<VirtualJoystickUIBackground>(**entity).is_some())?;
-    joystick_rect(world, *base)
+    joystick_frame(world, *base)
+}
+
+/// The [`NodeFrame`] of the [`JoystickBaseBounds`] of the joystick, [`None`] if unbounded.
+fn bounds_frame(
+    world: &mut World,
+    entity: Entity,
+    bounds: JoystickBaseBounds,
+) -> Option<NodeFrame> {
+    match bounds {
+        JoystickBaseBounds::Unbounded => None,
+        JoystickBaseBounds::InteractionArea => {
+            let window_size = world
+                .query_filtered::<&Window, With<PrimaryWindow>>()
+                .single(world)
+                .ok()
+                .map(Window::size);
+            let area = InteractionArea::resolve(
+                entity,
+                world.get::<JoystickInteractionRegion>(entity).copied(),
+                window_size,
+                world.get::<Children>(entity),
+                |area| joystick_frame(world, area).is_some(),
+                |area| world.get::<VirtualJoystickInteractionArea>(area).is_some(),
+            );
+            match area {
+                InteractionArea::Screen(rect) => Some(NodeFrame::from_rect(rect)),
+                InteractionArea::Node(area) => joystick_frame(world, area),
+            }
+        }
+        JoystickBaseBounds::Parent => {
+            let parent = world.get::<ChildOf>(entity)?.parent();
+            joystick_frame(world, parent)
+        }
+        JoystickBaseBounds::Window => {
+            let window = world
+                .query_filtered::<&Window, With<PrimaryWindow>>()
+                .single(world)
+                .ok()?;
+            Some(NodeFrame::from_rect(Rect::from_corners(
+                Vec2::ZERO,
+                window.size(),
+            )))
+        }
+    }
+}
+
+/// Clamp a [`VirtualJoystickState::base_offset`] so the base stays inside `bounds`.
+///
+/// The base is clamped in the local space of `bounds`, taking the rotation and scale of both the
+/// joystick and `bounds` into account. If the base does not fit it is centered.
+fn clamp_base_offset(
+    base_offset: Vec2,
+    base_half_size: Vec2,
+    joystick_frame: NodeFrame,
+    bounds: Option<NodeFrame>,
+) -> Vec2 {
+    let Some(bounds) = bounds else {
+        return base_offset;
+    };
+    // Center and half extent of the base in the local space of `bounds`.
+    let center = bounds.local_point(joystick_frame.screen_point(base_offset + base_half_size));
+    let to_bounds = |offset: Vec2| bounds.to_local(joystick_frame.to_screen(offset)).abs();
+    let half_extent =
+        to_bounds(Vec2::new(base_half_size.x, 0.)) + to_bounds(Vec2::new(0., base_half_size.y));
+
+    let bounds_half_size = bounds.rect.half_size();
+    let slack = (bounds_half_size - half_extent).max(Vec2::ZERO);
+    let clamped = bounds_half_size + (center - bounds_half_size).clamp(-slack, slack);
+    base_offset + joystick_frame.to_local(bounds.to_screen(clamped - center))
 }
 
 /// Update [`VirtualJoystickState::base_offset`] and return the associated [`TouchState`] as an [`Option`].
-fn update_base_offset(state: &mut VirtualJoystickState, rect: Rect) -> Option<&TouchState> {
+fn update_base_offset(
+    state: &mut VirtualJoystickState,
+    joystick_frame: NodeFrame,
+    base_frame: NodeFrame,
+    bounds: Option<NodeFrame>,
+) -> Option<&TouchState> {
     // Return None if `state.touch_state` is `None` and set `state.base_offset` to ZERO if joystick was just released.
     let Some(touch_state) = &state.touch_state else {
         if state.just_released {
@@ -245,7 +1172,9 @@ fn update_base_offset(state: &mut VirtualJoystickState, rect: Rect) -> Option<&T
 
     // Center `state.base_offset` from starting point if joystick was just pressed and return `touch_state`.
     if touch_state.just_pressed {
-        state.base_offset = touch_state.start - rect.center();
+        let base_half_size = base_frame.rect.half_size();
+        let base_offset = joystick_frame.local_point(touch_state.start) - base_half_size;
+        state.base_offset = clamp_base_offset(base_offset, base_half_size, joystick_frame, bounds);
     }
     Some(touch_state)
 }
@@ -291,3 +1220,95 @@ fn base_offset_delta(rect: Rect, offset: Vec2) -> Option<Vec2> {
     let offset = offset * half_size_x / distance;
     Some(offset * (1. - half_size_x / distance))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::components::TouchState;
+
+    #[test]
+    fn smoothing_returns_to_held_delta() {
+        let smoothing = JoystickSmoothing::default();
+        let held = Vec2::new(0.5, 0.);
+        let mut state = VirtualJoystickState {
+            touch_state: Some(TouchState::from_touch_pos(0, Vec2::ZERO)),
+            delta: Vec2::X,
+            ..Default::default()
+        };
+        let mut smoothed = JoystickSmoothingState {
+            delta: Vec2::X,
+            ..Default::default()
+        };
+        smoothing.constrain(&mut state, &mut smoothed, 0.);
+
+        // Released, with the delta held like `JoystickSticky` does.
+        state.touch_state = None;
+        state.just_released = true;
+        state.delta = held;
+        smoothing.constrain(&mut state, &mut smoothed, 0.05);
+        assert!(smoothed.delta.x > held.x);
+        state.just_released = false;
+        for _ in 0..10 {
+            smoothing.constrain(&mut state, &mut smoothed, 0.05);
+            assert!(smoothed.delta.x >= held.x);
+        }
+        assert_eq!(smoothed.delta, held);
+    }
+
+    /// Frame of a joystick centered on `center`, rotated by a quarter turn and scaled twice.
+    fn turned_frame(center: Vec2, size: f32) -> NodeFrame {
+        NodeFrame {
+            rect: Rect::from_center_size(center, Vec2::splat(size)),
+            matrix: Mat2::from_angle(FRAC_PI_2) * 2.,
+        }
+    }
+
+    #[test]
+    fn bases_of_turned_joysticks_stay_in_bounds() {
+        let joystick_frame = turned_frame(Vec2::splat(100.), 200.);
+        let bounds = NodeFrame::from_rect(Rect::new(0., 0., 400., 400.));
+        let base_half_size = Vec2::splat(50.);
+
+        // A base in the top left corner of the joystick pokes out of the top of the screen.
+        let base_offset =
+            clamp_base_offset(Vec2::ZERO, base_half_size, joystick_frame, Some(bounds));
+        for corner in [Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::ONE] {
+            let corner = joystick_frame.screen_point(base_offset + corner * base_half_size * 2.);
+            assert!(
+                bounds.rect.inflate(1e-3).contains(corner),
+                "{corner} is out of bounds"
+            );
+        }
+        assert!(
+            joystick_frame
+                .screen_point(base_offset + base_half_size)
+                .abs_diff_eq(Vec2::new(200., 100.), 1e-3)
+        );
+    }
+
+    #[test]
+    fn dynamic_max_distance_is_in_logical_pixels() {
+        let joystick_frame = turned_frame(Vec2::splat(200.), 200.);
+        let base_frame = turned_frame(Vec2::splat(200.), 100.);
+        let follow = JoystickDynamicFollow {
+            max_distance: Some(50.),
+            ..JoystickDynamicFollow::RIGID
+        };
+        let mut state = VirtualJoystickState {
+            touch_state: Some(TouchState::from_touch_pos(0, Vec2::splat(200.))),
+            ..Default::default()
+        };
+        JoystickDynamic.knob_delta(&mut state, joystick_frame, base_frame, None, &follow, 0.);
+        let pressed_offset = state.base_offset;
+
+        // Drag far past the rim, the base may only follow `max_distance` on screen.
+        let touch_state = state.touch_state.as_mut().unwrap();
+        touch_state.just_pressed = false;
+        touch_state.current = Vec2::new(600., 200.);
+        JoystickDynamic.knob_delta(&mut state, joystick_frame, base_frame, None, &follow, 0.);
+        let distance = joystick_frame.to_screen(state.base_offset - pressed_offset);
+        assert!((distance.length() - 50.).abs() < 1e-3, "{distance}");
+    }
+}
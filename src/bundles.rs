@@ -4,10 +4,10 @@ use bevy::{
     ui::{ComputedNode, Node, UiGlobalTransform, UiTransform, ZIndex},
 };
 
-use crate::{VirtualJoystickID, VirtualJoystickNode};
+use crate::{JoystickId, VirtualJoystickID, VirtualJoystickNode};
 
 #[derive(Bundle, Debug, Default)]
-pub struct VirtualJoystickBundle<S: VirtualJoystickID> {
+pub struct VirtualJoystickBundle<S: VirtualJoystickID = JoystickId> {
     /// Describes the size of the node
     pub(crate) node: ComputedNode,
     /// Describes the style including flexbox settings
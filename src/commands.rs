@@ -0,0 +1,214 @@
+use std::{any::TypeId, sync::Arc};
+
+use bevy::{
+    ecs::{
+        component::{Component, Mutable},
+        system::EntityCommands,
+        world::EntityWorldMut,
+    },
+    prelude::{Vec2, Visibility},
+};
+
+use crate::{
+    JoystickId, VirtualJoystickAction, VirtualJoystickBehavior, VirtualJoystickID,
+    VirtualJoystickNode,
+    behavior::{
+        JoystickBaseBounds, JoystickBehaviors, JoystickDeadZone, JoystickDynamic,
+        JoystickDynamicFollow, JoystickFade, JoystickFixed, JoystickFloating,
+        JoystickHorizontalOnly, JoystickInvisible, JoystickSmoothing, JoystickSticky,
+        JoystickVerticalOnly, NoBehavior,
+    },
+    components::{
+        JoystickFadeState, JoystickHiddenByBehavior, JoystickSmoothedByBehavior,
+        JoystickSmoothingState, JoystickStickyState, VirtualJoystickState,
+    },
+    systems::release_joystick,
+    typed::{JoystickTypedId, TypedAction, TypedJoystick},
+};
+
+/// [`EntityCommands`] to change joysticks at runtime.
+pub trait JoystickCommandsExt {
+    /// Replace the behavior of the joystick, removing the state left by the previous behavior.
+    ///
+    /// Behavior components like [`crate::JoystickFloating`], [`crate::JoystickBehaviors`] and
+    /// [`NoBehavior`] are removed with their settings and state so only the new behavior runs,
+    /// colors faded by [`crate::JoystickFade`] are restored. An active touch is kept and picked up
+    /// by the new behavior.
+    fn set_joystick_behavior(&mut self, behavior: impl VirtualJoystickBehavior) -> &mut Self;
+
+    /// Run `behavior` after the current behavior of the joystick, keeping its state.
+    ///
+    /// With [`NoBehavior`], `behavior` runs after the behavior components instead and
+    /// [`NoBehavior`] is removed.
+    fn push_joystick_behavior(&mut self, behavior: impl VirtualJoystickBehavior) -> &mut Self;
+
+    /// Replace the action of the joystick.
+    ///
+    /// `S` is the [`VirtualJoystickID`] the joystick was spawned with, see [`JoystickTypedId`].
+    /// An active drag is ended on the previous action and started on the new one.
+    fn set_joystick_action<S: VirtualJoystickID>(
+        &mut self,
+        action: impl VirtualJoystickAction<S>,
+    ) -> &mut Self;
+
+    /// Release the active touch of the joystick with a [`crate::VirtualJoystickMessageType::Up`]
+    /// and reset it to its initial state, keeping its behavior and action.
+    fn reset_joystick(&mut self) -> &mut Self;
+}
+
+impl JoystickCommandsExt for EntityCommands<'_> {
+    fn set_joystick_behavior(&mut self, behavior: impl VirtualJoystickBehavior) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let Some(mut node) = entity.get_mut::<VirtualJoystickNode>() else {
+                return;
+            };
+            node.behavior = Arc::new(behavior);
+            let behavior = Arc::clone(&node.behavior);
+            TypedJoystick::set_behavior(&mut entity, behavior);
+            entity.remove::<(BehaviorComponents, BehaviorState)>();
+            reset_behavior_state(&mut entity);
+        })
+    }
+
+    fn push_joystick_behavior(&mut self, behavior: impl VirtualJoystickBehavior) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let no_behavior = entity.contains::<NoBehavior>();
+            let Some(mut node) = entity.get_mut::<VirtualJoystickNode>() else {
+                return;
+            };
+            // NOTE: The trait object is ignored with `NoBehavior`, so it is replaced.
+            if no_behavior {
+                node.behavior = Arc::new(behavior);
+            } else {
+                node.behavior = Arc::new((Arc::clone(&node.behavior), behavior));
+            }
+            let behavior = Arc::clone(&node.behavior);
+            TypedJoystick::set_behavior(&mut entity, behavior);
+            if no_behavior {
+                entity.remove::<NoBehavior>();
+            }
+        })
+    }
+
+    fn set_joystick_action<S: VirtualJoystickID>(
+        &mut self,
+        action: impl VirtualJoystickAction<S>,
+    ) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let Some(id) = typed_id::<S>(&entity) else {
+                return;
+            };
+            let action: Arc<dyn VirtualJoystickAction<S>> = Arc::new(action);
+            // Keep the typed node in sync, it is the source of truth of typed joysticks.
+            if TypeId::of::<S>() != TypeId::of::<JoystickId>()
+                && let Some(mut node) = entity.get_mut::<VirtualJoystickNode<S>>()
+            {
+                node.action = Arc::clone(&action);
+            }
+            let action: Arc<dyn VirtualJoystickAction<JoystickId>> =
+                Arc::new(TypedAction::new(id, action));
+            let Some(mut node) = entity.get_mut::<VirtualJoystickNode>() else {
+                return;
+            };
+            let id = node.id.clone();
+            let previous = std::mem::replace(&mut node.action, Arc::clone(&action));
+
+            // Hand an active drag over to the new action.
+            let Some(state) = entity
+                .get::<VirtualJoystickState>()
+                .filter(|state| state.touch_state.is_some())
+                .cloned()
+            else {
+                return;
+            };
+            let entity_id = entity.id();
+            entity.world_scope(|world| {
+                previous.on_end_drag(id.clone(), state.clone(), world, entity_id);
+                action.on_start_drag(id, state, world, entity_id);
+            });
+        })
+    }
+
+    fn reset_joystick(&mut self) -> &mut Self {
+        self.queue(|mut entity: EntityWorldMut| {
+            let entity_id = entity.id();
+            entity.world_scope(|world| release_joystick(world, entity_id));
+            if let Some(mut state) = entity.get_mut::<VirtualJoystickState>() {
+                *state = VirtualJoystickState::default();
+            }
+            reset_behavior_state(&mut entity);
+        })
+    }
+}
+
+/// The typed id of `entity`, see [`JoystickTypedId`].
+fn typed_id<S: VirtualJoystickID>(entity: &EntityWorldMut) -> Option<S> {
+    if let Some(id) = entity.get::<JoystickTypedId<S>>() {
+        return Some(id.0.clone());
+    }
+    S::from_reflect(&entity.get::<VirtualJoystickNode>()?.id)
+}
+
+/// Behavior components removed by [`JoystickCommandsExt::set_joystick_behavior`].
+type BehaviorComponents = (
+    NoBehavior,
+    JoystickBehaviors,
+    JoystickFixed,
+    JoystickFloating,
+    JoystickDynamic,
+    JoystickDynamicFollow,
+    JoystickBaseBounds,
+    JoystickSticky,
+    JoystickDeadZone,
+    JoystickHorizontalOnly,
+    JoystickVerticalOnly,
+    JoystickInvisible,
+    JoystickFade,
+    JoystickSmoothing,
+);
+
+/// State kept by behaviors, removed by [`JoystickCommandsExt::set_joystick_behavior`].
+///
+/// [`crate::JoystickVisualStates`] inserted by [`JoystickFade`] are removed by
+/// [`crate::systems::update_visual_states`] once it restored the faded colors.
+type BehaviorState = (
+    JoystickFadeState,
+    JoystickSmoothingState,
+    JoystickSmoothedByBehavior,
+    JoystickStickyState,
+);
+
+/// Clear the state behaviors keep on a joystick, so a new behavior starts from scratch.
+///
+/// The state of a dragged joystick is kept for the new behavior to pick up.
+fn reset_behavior_state(entity: &mut EntityWorldMut) {
+    // Show the joystick again if the previous behavior hid it.
+    if entity.take::<JoystickHiddenByBehavior>().is_some()
+        && let Some(mut visibility) = entity.get_mut::<Visibility>()
+    {
+        *visibility = Visibility::Inherited;
+    }
+    if entity
+        .get::<VirtualJoystickState>()
+        .is_some_and(|state| state.touch_state.is_some())
+    {
+        return;
+    }
+
+    // NOTE: Behavior components kept by `reset_joystick` require their state, so it is reset
+    //       instead of removed.
+    reset_component::<JoystickSmoothingState>(entity);
+    reset_component::<JoystickStickyState>(entity);
+    reset_component::<JoystickFadeState>(entity);
+    if let Some(mut state) = entity.get_mut::<VirtualJoystickState>() {
+        state.base_offset = Vec2::ZERO;
+        state.delta = Vec2::ZERO;
+    }
+}
+
+/// Set the component `C` of `entity` back to its default, if present.
+fn reset_component<C: Component<Mutability = Mutable> + Default>(entity: &mut EntityWorldMut) {
+    if let Some(mut component) = entity.get_mut::<C>() {
+        *component = C::default();
+    }
+}
@@ -1,8 +1,12 @@
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 
 use bevy::{
-    ecs::{component::Component, reflect::ReflectComponent},
-    prelude::Vec2,
+    ecs::{
+        component::Component, entity::Entity, reflect::ReflectComponent, system::EntityCommands,
+        world::EntityWorldMut,
+    },
+    math::Rect,
+    prelude::{Children, Vec2, Visibility},
     reflect::{Reflect, std_traits::ReflectDefault},
 };
 #[cfg(feature = "inspect")]
@@ -11,8 +15,8 @@ use bevy_inspector_egui::InspectorOptions;
 use bevy_inspector_egui::prelude::ReflectInspectorOptions;
 
 use crate::{
-    VirtualJoystickAction, VirtualJoystickBehavior, VirtualJoystickID, action::NoAction,
-    behavior::JoystickFloating,
+    JoystickId, VirtualJoystickAction, VirtualJoystickBehavior, VirtualJoystickID,
+    action::NoAction, behavior::JoystickFloating,
 };
 
 #[derive(Component, Copy, Clone, Debug, Default, Reflect)]
@@ -33,10 +37,136 @@ pub struct VirtualJoystickUIBackground;
 #[cfg_attr(feature = "inspect", reflect(InspectorOptions))]
 pub struct VirtualJoystickInteractionArea;
 
+/// Interaction area of a joystick outside of its own subtree.
+///
+/// Presses inside the region activate the joystick instead of presses inside its
+/// [`VirtualJoystickInteractionArea`] child or the joystick node, so a small joystick can be
+/// activated from a whole side of the screen. If the node or the primary window is missing the
+/// joystick falls back to its own interaction area.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub enum JoystickInteractionRegion {
+    /// Any UI node, anywhere in the UI tree.
+    Node(#[entities] Entity),
+    /// Region of the primary window in fractions of its size, from `(0, 0)` at the top left to
+    /// `(1, 1)` at the bottom right.
+    Screen(Rect),
+}
+
+impl JoystickInteractionRegion {
+    /// Region of the primary window in fractions of its size, see [`Self::Screen`].
+    pub fn screen(min: Vec2, max: Vec2) -> Self {
+        Self::Screen(Rect::from_corners(min, max))
+    }
+
+    /// The left half of the primary window.
+    pub fn left_half() -> Self {
+        Self::screen(Vec2::ZERO, Vec2::new(0.5, 1.))
+    }
+
+    /// The right half of the primary window.
+    pub fn right_half() -> Self {
+        Self::screen(Vec2::new(0.5, 0.), Vec2::ONE)
+    }
+
+    /// The region on screen in logical pixels for a window of `size`, [`None`] for a node.
+    pub(crate) fn screen_rect(self, size: Vec2) -> Option<Rect> {
+        match self {
+            Self::Node(_) => None,
+            Self::Screen(rect) => Some(Rect {
+                min: rect.min * size,
+                max: rect.max * size,
+            }),
+        }
+    }
+}
+
+impl Default for JoystickInteractionRegion {
+    fn default() -> Self {
+        Self::screen(Vec2::ZERO, Vec2::ONE)
+    }
+}
+
+/// Where presses activate a joystick, and where [`crate::JoystickBaseBounds::InteractionArea`]
+/// keeps its base.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum InteractionArea {
+    /// Region of the primary window in logical pixels.
+    Screen(Rect),
+    /// A UI node, the joystick itself if it has no other interaction area.
+    Node(Entity),
+}
+
+impl InteractionArea {
+    /// The interaction area of `joystick`: its [`JoystickInteractionRegion`], else its
+    /// [`VirtualJoystickInteractionArea`] child, else the joystick node.
+    ///
+    /// `window_size` is the logical size of the primary window, `is_node` tells whether an entity
+    /// is a laid out UI node and `is_interaction_area` whether it has a
+    /// [`VirtualJoystickInteractionArea`].
+    pub(crate) fn resolve(
+        joystick: Entity,
+        region: Option<JoystickInteractionRegion>,
+        window_size: Option<Vec2>,
+        children: Option<&Children>,
+        is_node: impl Fn(Entity) -> bool,
+        is_interaction_area: impl Fn(Entity) -> bool,
+    ) -> Self {
+        match region {
+            Some(JoystickInteractionRegion::Node(area)) if is_node(area) => {
+                return Self::Node(area);
+            }
+            Some(region @ JoystickInteractionRegion::Screen(_)) => {
+                if let Some(rect) = window_size.and_then(|size| region.screen_rect(size)) {
+                    return Self::Screen(rect);
+                }
+            }
+            _ => {}
+        }
+        let area = children.and_then(|children| {
+            children
+                .iter()
+                .copied()
+                .find(|&child| is_interaction_area(child))
+        });
+        Self::Node(area.unwrap_or(joystick))
+    }
+}
+
+/// Indicator on the rim of the base, rotated so its top points in the direction of the knob.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "inspect", derive(InspectorOptions))]
+#[cfg_attr(feature = "inspect", reflect(InspectorOptions))]
+pub struct VirtualJoystickUIDirection;
+
+/// Band stretched from the center of the base to the knob.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "inspect", derive(InspectorOptions))]
+#[cfg_attr(feature = "inspect", reflect(InspectorOptions))]
+pub struct VirtualJoystickUIBand;
+
+/// Ring centered on the base, scaled by the magnitude of the delta.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "inspect", derive(InspectorOptions))]
+#[cfg_attr(feature = "inspect", reflect(InspectorOptions))]
+pub struct VirtualJoystickUIIntensity;
+
+/// Ignore the input of a joystick, releasing its active touch with a
+/// [`crate::VirtualJoystickMessageType::Up`] and showing [`crate::JoystickVisualState::Disabled`].
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "inspect", derive(InspectorOptions))]
+#[cfg_attr(feature = "inspect", reflect(InspectorOptions))]
+pub struct JoystickDisabled;
+
 #[derive(Component, Reflect)]
 #[reflect(Component, Default)]
-pub struct VirtualJoystickNode<S: VirtualJoystickID> {
+pub struct VirtualJoystickNode<S: VirtualJoystickID = JoystickId> {
     pub id: S,
+    /// Not reflected, use [`crate::JoystickBehaviors`] for behaviors editable in inspectors and scenes.
     #[reflect(ignore)]
     pub behavior: Arc<dyn VirtualJoystickBehavior>,
     #[reflect(ignore)]
@@ -66,8 +196,106 @@ impl<S: VirtualJoystickID> std::fmt::Debug for VirtualJoystickNode<S> {
 pub struct VirtualJoystickState {
     pub touch_state: Option<TouchState>,
     pub just_released: bool,
+    /// Whether the release of this frame aborted the touch instead of lifting it.
+    ///
+    /// Set together with [`Self::just_released`] when the OS cancels the touch, the touch
+    /// disappears, the window loses focus, the mouse leaves the window or the app is suspended.
+    pub just_canceled: bool,
     pub base_offset: Vec2,
+    /// Knob delta ranging from -1 to 1 in the local space of the joystick.
     pub delta: Vec2,
+    /// Filtered pointer velocity in logical pixels per second, see [`TouchState::velocity`].
+    ///
+    /// Kept for the frame the joystick is released in, zero while idle.
+    pub velocity: Vec2,
+    /// Filtered pointer acceleration, see [`TouchState::acceleration`].
+    pub acceleration: Vec2,
+    /// Distance travelled by the pointer since the press, see [`TouchState::travel`].
+    pub travel: f32,
+}
+
+/// Space in which [`crate::VirtualJoystickMessage::axis`] is reported for rotated joysticks.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+pub enum JoystickOutputSpace {
+    /// Axes follow the rotation of the joystick, like [`VirtualJoystickState::delta`].
+    #[default]
+    Local,
+    /// Axes are aligned with the screen.
+    Screen,
+}
+
+/// Smoothed knob and base placement maintained by [`crate::JoystickSmoothing`].
+///
+/// While the joystick is smoothed, the UI is positioned from these values instead of
+/// [`VirtualJoystickState`].
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickSmoothingState {
+    pub delta: Vec2,
+    pub base_offset: Vec2,
+    /// Rate of change of [`Self::delta`], used by the critically damped filter.
+    pub velocity: Vec2,
+    /// Delta and base offset at release, and the time elapsed since then.
+    pub release: Option<(Vec2, Vec2, f32)>,
+}
+
+/// Opacity of a joystick faded by [`crate::JoystickFade`].
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickFadeState {
+    pub opacity: f32,
+    /// Time in seconds since release, used to linger before fading out.
+    pub linger: f32,
+}
+
+/// Opacity last multiplied into the borders, outlines and descendants of a joystick part by
+/// [`crate::JoystickVisualStates`], so the colors set by users can be kept.
+#[derive(Component, Clone, Copy, Debug)]
+pub(crate) struct JoystickPartOpacity(pub(crate) f32);
+
+/// Marks [`crate::JoystickVisualStates`] inserted by [`crate::JoystickFade`], so they are removed
+/// once the fade is gone and its opacity is undone.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(crate) struct JoystickFadeVisualStates;
+
+/// Marks a joystick smoothed by a [`crate::JoystickSmoothing`] trait object, which keeps a
+/// [`JoystickSmoothingState`] without the component.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(crate) struct JoystickSmoothedByBehavior;
+
+/// Marks a joystick hidden by [`crate::JoystickInvisible`] or [`crate::JoystickFade`], so
+/// [`crate::JoystickCommandsExt`] only shows joysticks this crate hid.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(crate) struct JoystickHiddenByBehavior;
+
+impl JoystickHiddenByBehavior {
+    /// Track the `visibility` a behavior just gave to `entity`.
+    pub(crate) fn track(entity: &mut EntityWorldMut, visibility: Visibility) {
+        if visibility == Visibility::Hidden {
+            entity.insert(Self);
+        } else {
+            entity.remove::<Self>();
+        }
+    }
+
+    /// Like [`Self::track`] from a system.
+    pub(crate) fn track_deferred(entity: &mut EntityCommands, visibility: Visibility) {
+        if visibility == Visibility::Hidden {
+            entity.insert(Self);
+        } else {
+            entity.remove::<Self>();
+        }
+    }
+}
+
+/// Delta held by [`crate::JoystickSticky`] while the joystick is released.
+#[derive(Component, Clone, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickStickyState {
+    pub held: Vec2,
+    /// Offset between the held delta and the pointer when re-grabbed relatively.
+    pub grab_offset: Option<Vec2>,
 }
 
 impl<S: VirtualJoystickID> VirtualJoystickNode<S> {
@@ -95,33 +323,95 @@ pub struct TouchState {
     pub start: Vec2,
     pub current: Vec2,
     pub just_pressed: bool,
+    /// Time in seconds since the touch started.
+    pub duration: f32,
+    /// Samples of the last [`Self::HISTORY_WINDOW`] seconds, oldest first.
+    pub history: VecDeque<TouchSample>,
+    /// Pointer velocity in logical pixels per second, averaged over [`Self::history`].
+    ///
+    /// Like [`Self::current`], the y axis points down.
+    pub velocity: Vec2,
+    /// Pointer acceleration in logical pixels per second squared, averaged over [`Self::history`].
+    pub acceleration: Vec2,
+    /// Length of the path travelled by the pointer since the press.
+    pub travel: f32,
+}
+
+/// Position of a touch at a point in time.
+#[derive(Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Default)]
+pub struct TouchSample {
+    /// Time in seconds since the touch started.
+    pub time: f32,
+    pub position: Vec2,
 }
 
 impl TouchState {
+    /// Time span in seconds covered by [`Self::history`].
+    pub const HISTORY_WINDOW: f32 = 0.1;
+
     /// Set new [`Self::current`].
     pub fn set_new_current(&mut self, new_current: Vec2) {
         if self.current != new_current {
             self.current = new_current;
         }
     }
+    /// Record [`Self::current`] at [`Self::duration`] and update the filtered velocity,
+    /// acceleration and travel distance.
+    pub fn push_sample(&mut self) {
+        if let Some(last) = self.history.back() {
+            self.travel += last.position.distance(self.current);
+        }
+        self.history.push_back(TouchSample {
+            time: self.duration,
+            position: self.current,
+        });
+        // Drop samples older than the window, keeping one sample before it so it stays covered.
+        while self.history.len() > 2 && self.history[1].time <= self.duration - Self::HISTORY_WINDOW
+        {
+            self.history.pop_front();
+        }
+
+        let (first, last) = (self.history[0], self.history[self.history.len() - 1]);
+        self.velocity = sample_velocity(first, last);
+
+        // Compare the velocities of both halves of the window to estimate the acceleration.
+        let middle = self.history[self.history.len() / 2];
+        self.acceleration = if self.history.len() > 2 && last.time > first.time {
+            (sample_velocity(middle, last) - sample_velocity(first, middle))
+                / ((last.time - first.time) * 0.5)
+        } else {
+            Vec2::ZERO
+        };
+    }
     /// Initialize as touch state from touch position.
     pub fn from_touch_pos(id: u64, pos: Vec2) -> Self {
-        Self {
-            id,
-            is_mouse: false,
-            start: pos,
-            current: pos,
-            just_pressed: true,
-        }
+        Self::new(id, false, pos)
     }
     /// Initialize as mouse state from mouse position.
     pub fn from_mouse_pos(id: u64, pos: Vec2) -> Self {
-        Self {
+        Self::new(id, true, pos)
+    }
+
+    fn new(id: u64, is_mouse: bool, pos: Vec2) -> Self {
+        let mut touch_state = Self {
             id,
-            is_mouse: true,
+            is_mouse,
             start: pos,
             current: pos,
             just_pressed: true,
-        }
+            ..Default::default()
+        };
+        touch_state.push_sample();
+        touch_state
+    }
+}
+
+/// Average velocity between two [`TouchSample`]s.
+fn sample_velocity(from: TouchSample, to: TouchSample) -> Vec2 {
+    let time = to.time - from.time;
+    if time <= f32::EPSILON {
+        return Vec2::ZERO;
     }
+    (to.position - from.position) / time
 }
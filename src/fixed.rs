@@ -0,0 +1,79 @@
+use bevy::{
+    ecs::{entity::Entity, reflect::ReflectResource, resource::Resource},
+    platform::collections::HashMap,
+    prelude::Vec2,
+    reflect::Reflect,
+};
+
+use crate::{JoystickId, VirtualJoystickID};
+
+/// Joystick input for systems running in `FixedUpdate`.
+///
+/// Latched at the start of every fixed step, so every step of a frame sees the same input.
+/// Press and release edges are accumulated until the next step, so none are lost on frames
+/// without fixed steps, and they are only seen by the first step of a frame.
+#[derive(Resource, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct FixedJoystickInput<S: VirtualJoystickID = JoystickId> {
+    pub(crate) latched: HashMap<Entity, FixedJoystickState<S>>,
+    pub(crate) pending: HashMap<Entity, FixedJoystickState<S>>,
+}
+
+impl<S: VirtualJoystickID> Default for FixedJoystickInput<S> {
+    fn default() -> Self {
+        Self {
+            latched: HashMap::default(),
+            pending: HashMap::default(),
+        }
+    }
+}
+
+impl<S: VirtualJoystickID> FixedJoystickInput<S> {
+    /// Input of the joystick with `id`.
+    pub fn get(&self, id: &S) -> Option<&FixedJoystickState<S>>
+    where
+        S: PartialEq,
+    {
+        self.latched.values().find(|state| state.id == *id)
+    }
+
+    /// Input of the joystick `entity`.
+    pub fn entity(&self, entity: Entity) -> Option<&FixedJoystickState<S>> {
+        self.latched.get(&entity)
+    }
+
+    /// Input of every joystick with its entity.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &FixedJoystickState<S>)> {
+        self.latched.iter().map(|(entity, state)| (*entity, state))
+    }
+}
+
+impl<S: VirtualJoystickID> FixedJoystickState<S> {
+    /// The same input with the typed `id`, see [`crate::JoystickTypedId`].
+    pub(crate) fn with_id<T: VirtualJoystickID>(&self, id: T) -> FixedJoystickState<T> {
+        FixedJoystickState {
+            id,
+            delta: self.delta,
+            pressed: self.pressed,
+            just_pressed: self.just_pressed,
+            just_released: self.just_released,
+            just_canceled: self.just_canceled,
+        }
+    }
+}
+
+/// Input of one joystick latched for a fixed step, see [`FixedJoystickInput`].
+#[derive(Clone, Debug, Default, Reflect)]
+pub struct FixedJoystickState<S: VirtualJoystickID = JoystickId> {
+    pub id: S,
+    /// Delta like [`crate::VirtualJoystickMessage::axis`].
+    pub delta: Vec2,
+    pub pressed: bool,
+    /// The joystick was pressed since the previous fixed step.
+    pub just_pressed: bool,
+    /// The joystick was released or canceled since the previous fixed step.
+    pub just_released: bool,
+    /// The joystick was canceled since the previous fixed step, see
+    /// [`crate::VirtualJoystickState::just_canceled`].
+    pub just_canceled: bool,
+}
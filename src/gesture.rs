@@ -0,0 +1,121 @@
+use bevy::{
+    ecs::{component::Component, entity::Entity, message::Message, reflect::ReflectComponent},
+    prelude::Vec2,
+    reflect::{Reflect, std_traits::ReflectDefault},
+};
+#[cfg(feature = "inspect")]
+use bevy_inspector_egui::InspectorOptions;
+#[cfg(feature = "inspect")]
+use bevy_inspector_egui::prelude::ReflectInspectorOptions;
+
+use crate::{JoystickId, VirtualJoystickID};
+
+/// Opt-in gesture recognition for a joystick.
+///
+/// Insert this next to a [`crate::VirtualJoystickNode`] to receive
+/// [`VirtualJoystickGestureMessage`]s. Recognition runs alongside the normal drag pipeline,
+/// so the joystick keeps sending its regular [`crate::VirtualJoystickMessage`]s.
+///
+/// Durations are in seconds, distances in logical pixels and speeds in logical pixels per second.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[require(JoystickGestureState)]
+#[cfg_attr(feature = "inspect", derive(InspectorOptions))]
+#[cfg_attr(feature = "inspect", reflect(InspectorOptions))]
+pub struct JoystickGestures {
+    /// Maximum time a touch may be held to count as a tap.
+    pub tap_max_duration: f32,
+    /// Maximum distance the pointer may travel from its start to count as a tap or long press.
+    pub tap_max_distance: f32,
+    /// Maximum time between two taps to count as a double tap.
+    pub double_tap_interval: f32,
+    /// Time a touch has to be held in place to count as a long press.
+    pub long_press_duration: f32,
+    /// Maximum time a touch may be held to count as a flick.
+    pub flick_max_duration: f32,
+    /// Minimum speed of the pointer at release to count as a flick.
+    pub flick_min_speed: f32,
+}
+
+impl Default for JoystickGestures {
+    fn default() -> Self {
+        Self {
+            tap_max_duration: 0.25,
+            tap_max_distance: 10.,
+            double_tap_interval: 0.3,
+            long_press_duration: 0.5,
+            flick_max_duration: 0.3,
+            flick_min_speed: 600.,
+        }
+    }
+}
+
+/// Per joystick bookkeeping used by the gesture recognizer.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub(crate) struct JoystickGestureState {
+    /// Start, last known position and duration of the current touch.
+    pub touch: Option<(Vec2, Vec2, f32)>,
+    /// Largest distance from the start reached by the current touch.
+    pub travel: f32,
+    /// Whether a long press was already sent for the current touch.
+    pub long_press_sent: bool,
+    /// Elapsed time of the last tap that can still become a double tap.
+    pub last_tap: Option<f32>,
+}
+
+/// Gesture recognized on a joystick with [`JoystickGestures`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub enum JoystickGesture {
+    Tap,
+    /// Second tap within [`JoystickGestures::double_tap_interval`], sent instead of a [`Self::Tap`].
+    DoubleTap,
+    LongPress,
+    /// Fast swipe released within [`JoystickGestures::flick_max_duration`].
+    ///
+    /// `direction` is normalized and follows the axis convention of
+    /// [`crate::VirtualJoystickMessage::axis`], `speed` is in logical pixels per second.
+    Flick {
+        direction: Vec2,
+        speed: f32,
+    },
+}
+
+#[derive(Message, Debug)]
+pub struct VirtualJoystickGestureMessage<S: VirtualJoystickID = JoystickId> {
+    pub(crate) id: S,
+    pub(crate) entity: Entity,
+    pub(crate) gesture: JoystickGesture,
+    pub(crate) position: Vec2,
+}
+
+impl<S: VirtualJoystickID> VirtualJoystickGestureMessage<S> {
+    /// Get ID of the joystick that recognized the gesture
+    pub fn id(&self) -> S {
+        self.id.clone()
+    }
+
+    /// The same message with the typed `id`, see [`crate::JoystickTypedId`].
+    pub(crate) fn with_id<T: VirtualJoystickID>(&self, id: T) -> VirtualJoystickGestureMessage<T> {
+        VirtualJoystickGestureMessage {
+            id,
+            entity: self.entity,
+            gesture: self.gesture,
+            position: self.position,
+        }
+    }
+
+    /// Get the joystick [`Entity`] that recognized the gesture
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    /// Return the recognized [`JoystickGesture`]
+    pub fn gesture(&self) -> JoystickGesture {
+        self.gesture
+    }
+
+    /// Raw position of point (Mouse or Touch) where the gesture ended
+    pub fn position(&self) -> &Vec2 {
+        &self.position
+    }
+}
@@ -0,0 +1,151 @@
+use std::{borrow::Cow, fmt};
+
+#[cfg(feature = "serde")]
+use bevy::reflect::{ReflectDeserialize, ReflectSerialize};
+use bevy::{
+    ecs::intern::Interner,
+    reflect::{Reflect, std_traits::ReflectDefault},
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::VirtualJoystickID;
+
+/// A [`crate::VirtualJoystickID`] decided at runtime, for data driven layouts and mods.
+///
+/// This is the default id of [`crate::VirtualJoystickPlugin`], [`crate::VirtualJoystickNode`]
+/// and [`crate::VirtualJoystickMessage`], so a single plugin handles every joystick whatever
+/// its name, instead of a plugin and pipeline per compile-time id type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Reflect)]
+// NOTE: Reflected as a value, so reflected layouts and scenes are deserialized through
+//       `Deserialize`, which interns the name.
+#[reflect(opaque)]
+#[reflect(Default, Debug, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub enum JoystickId {
+    /// A named joystick.
+    ///
+    /// Names built at runtime are interned, so ids are cheap to clone every frame. Each distinct
+    /// name is allocated once and kept for the lifetime of the app.
+    Name(&'static str),
+    /// A numbered joystick, like a player or slot index.
+    Index(u32),
+}
+
+impl JoystickId {
+    pub fn name(name: impl Into<Cow<'static, str>>) -> Self {
+        match name.into() {
+            Cow::Borrowed(name) => Self::Name(name),
+            Cow::Owned(name) => Self::Name(intern(&name)),
+        }
+    }
+
+    pub const fn index(index: u32) -> Self {
+        Self::Index(index)
+    }
+
+    /// The runtime id of a typed [`crate::VirtualJoystickID`], see [`crate::JoystickTypedId`].
+    ///
+    /// Strings and `u32`s keep their value, other ids are named after their [`fmt::Debug`] output.
+    pub fn from_typed<S: VirtualJoystickID>(id: &S) -> Self {
+        let reflect = id.as_partial_reflect();
+        if let Some(id) = reflect.try_downcast_ref::<JoystickId>() {
+            id.clone()
+        } else if let Some(name) = reflect.try_downcast_ref::<String>() {
+            Self::Name(intern(name))
+        } else if let Some(index) = reflect.try_downcast_ref::<u32>() {
+            Self::Index(*index)
+        } else {
+            Self::Name(intern(&format!("{id:?}")))
+        }
+    }
+
+    /// Name of the joystick, if it is named.
+    pub fn as_name(&self) -> Option<&str> {
+        match self {
+            Self::Name(name) => Some(name),
+            Self::Index(_) => None,
+        }
+    }
+
+    /// Index of the joystick, if it is numbered.
+    pub fn as_index(&self) -> Option<u32> {
+        match self {
+            Self::Name(_) => None,
+            Self::Index(index) => Some(*index),
+        }
+    }
+}
+
+/// Names of [`JoystickId`]s built at runtime.
+static NAMES: Interner<str> = Interner::new();
+
+/// The interned `name`, see [`JoystickId::Name`].
+fn intern(name: &str) -> &'static str {
+    NAMES.intern(name).0
+}
+
+/// A deserialized [`JoystickId`], before its name is interned.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[serde(rename = "JoystickId")]
+enum OwnedJoystickId {
+    Name(String),
+    Index(u32),
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for JoystickId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match OwnedJoystickId::deserialize(deserializer)? {
+            OwnedJoystickId::Name(name) => name.into(),
+            OwnedJoystickId::Index(index) => Self::Index(index),
+        })
+    }
+}
+
+impl Default for JoystickId {
+    fn default() -> Self {
+        Self::Index(0)
+    }
+}
+
+impl fmt::Display for JoystickId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => f.write_str(name),
+            Self::Index(index) => write!(f, "#{index}"),
+        }
+    }
+}
+
+impl From<&'static str> for JoystickId {
+    fn from(name: &'static str) -> Self {
+        Self::Name(name)
+    }
+}
+
+impl From<String> for JoystickId {
+    fn from(name: String) -> Self {
+        Self::Name(intern(&name))
+    }
+}
+
+impl From<u32> for JoystickId {
+    fn from(index: u32) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl PartialEq<str> for JoystickId {
+    fn eq(&self, other: &str) -> bool {
+        self.as_name() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for JoystickId {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_name() == Some(*other)
+    }
+}
@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+#[cfg(feature = "serde")]
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    reflect::{TypeRegistryArc, serde::TypedReflectDeserializer},
+};
+#[cfg(feature = "serde")]
+use serde::de::DeserializeSeed;
+
+use crate::{
+    JoystickAnchor, JoystickBehaviors, JoystickId, JoystickInteractionRegion, JoystickSize,
+    JoystickThemeStyle, NoAction, NoBehavior, ProceduralJoystickStyle, create_procedural_joystick,
+};
+
+/// Joysticks decided at runtime, spawned by a [`JoystickLayoutRoot`].
+///
+/// With the `serde` feature, layouts are loaded from `.joysticks.ron` files holding a reflected
+/// `JoystickLayout`:
+///
+/// ```ron
+/// (
+///     joysticks: [
+///         (
+///             id: Name("move"),
+///             anchor: (point: BottomLeft, margin: Px((48.0, 48.0))),
+///             behaviors: ([Floating, DeadZone((0.1))]),
+///             interaction_region: Some(Screen((min: (0.0, 0.0), max: (0.5, 1.0)))),
+///         ),
+///     ],
+/// )
+/// ```
+#[derive(Asset, Clone, Debug, Default, Reflect)]
+#[reflect(Default)]
+pub struct JoystickLayout {
+    pub joysticks: Vec<JoystickLayoutEntry>,
+}
+
+/// One joystick of a [`JoystickLayout`], drawn with [`create_procedural_joystick`].
+#[derive(Clone, Debug, Default, Reflect)]
+#[reflect(Default)]
+pub struct JoystickLayoutEntry {
+    pub id: JoystickId,
+    pub anchor: JoystickAnchor,
+    pub size: JoystickSize,
+    /// Name of the [`crate::JoystickStyle`] of the [`crate::JoystickTheme`], see
+    /// [`JoystickThemeStyle`].
+    pub style: Option<String>,
+    pub behaviors: JoystickBehaviors,
+    pub interaction_region: Option<JoystickInteractionRegion>,
+}
+
+/// Spawns the joysticks of a [`JoystickLayout`] once it is loaded, and again each time it is
+/// modified.
+///
+/// The joysticks are not children of the root, so they are anchored to the window, but they are
+/// despawned with it, see [`JoystickLayoutJoysticks`].
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[require(JoystickLayoutJoysticks)]
+pub struct JoystickLayoutRoot(pub Handle<JoystickLayout>);
+
+/// The [`JoystickLayoutRoot`] that spawned a joystick.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+#[relationship(relationship_target = JoystickLayoutJoysticks)]
+pub struct JoystickLayoutOf(pub Entity);
+
+/// The joysticks spawned by a [`JoystickLayoutRoot`].
+#[derive(Component, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+#[relationship_target(relationship = JoystickLayoutOf, linked_spawn)]
+pub struct JoystickLayoutJoysticks(Vec<Entity>);
+
+impl JoystickLayoutEntry {
+    /// Logical size of the knob and the background, unless set by [`Self::size`].
+    const KNOB_SIZE: Vec2 = Vec2::splat(75.);
+    const BACKGROUND_SIZE: Vec2 = Vec2::splat(150.);
+
+    fn spawn(&self, cmd: &mut Commands, layout: Entity) -> Entity {
+        let joystick = create_procedural_joystick(
+            cmd,
+            self.id.clone(),
+            ProceduralJoystickStyle::default(),
+            None,
+            Self::KNOB_SIZE,
+            Self::BACKGROUND_SIZE,
+            Node {
+                width: Val::Px(Self::BACKGROUND_SIZE.x),
+                height: Val::Px(Self::BACKGROUND_SIZE.y),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            NoBehavior,
+            NoAction,
+        );
+        let mut entity = cmd.entity(joystick);
+        entity.insert((
+            NoBehavior,
+            self.behaviors.clone(),
+            self.anchor,
+            self.size,
+            JoystickLayoutOf(layout),
+        ));
+        if let Some(style) = &self.style {
+            entity.insert(JoystickThemeStyle(style.clone()));
+        }
+        if let Some(region) = self.interaction_region {
+            entity.insert(region);
+        }
+        joystick
+    }
+}
+
+/// Spawn the joysticks of new [`JoystickLayoutRoot`]s and of loaded or modified
+/// [`JoystickLayout`]s, replacing the joysticks spawned before.
+pub(crate) fn spawn_layouts(
+    mut cmd: Commands,
+    mut events: MessageReader<AssetEvent<JoystickLayout>>,
+    layouts: Res<Assets<JoystickLayout>>,
+    roots: Query<(Entity, Ref<JoystickLayoutRoot>)>,
+) {
+    let loaded: Vec<AssetId<JoystickLayout>> = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, root) in &roots {
+        if !root.is_changed() && !loaded.contains(&root.0.id()) {
+            continue;
+        }
+        let Some(layout) = layouts.get(&root.0) else {
+            continue;
+        };
+        cmd.entity(entity)
+            .despawn_related::<JoystickLayoutJoysticks>();
+        for joystick in &layout.joysticks {
+            joystick.spawn(&mut cmd, entity);
+        }
+    }
+}
+
+/// Loads a reflected [`JoystickLayout`] from a `.joysticks.ron` file.
+#[cfg(feature = "serde")]
+#[derive(TypePath)]
+pub struct JoystickLayoutLoader {
+    registry: TypeRegistryArc,
+}
+
+#[cfg(feature = "serde")]
+impl FromWorld for JoystickLayoutLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            registry: world.resource::<AppTypeRegistry>().0.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl AssetLoader for JoystickLayoutLoader {
+    type Asset = JoystickLayout;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<JoystickLayout, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
+        let registry = self.registry.read();
+        let layout = TypedReflectDeserializer::of::<JoystickLayout>(&registry)
+            .deserialize(&mut deserializer)?;
+        JoystickLayout::from_reflect(&*layout).ok_or_else(|| "invalid joystick layout".into())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["joysticks.ron"]
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_documented_layout() {
+        let mut app = App::new();
+        app.register_type::<JoystickLayout>();
+        let registry = app.world().resource::<AppTypeRegistry>().read();
+        let mut deserializer = ron::de::Deserializer::from_str(
+            r#"(
+                joysticks: [
+                    (
+                        id: Name("move"),
+                        anchor: (point: BottomLeft, margin: Px((48.0, 48.0))),
+                        behaviors: ([Floating, DeadZone((0.1))]),
+                        interaction_region: Some(Screen((min: (0.0, 0.0), max: (0.5, 1.0)))),
+                    ),
+                ],
+            )"#,
+        )
+        .unwrap();
+        let layout = TypedReflectDeserializer::of::<JoystickLayout>(&registry)
+            .deserialize(&mut deserializer)
+            .unwrap();
+        let layout = JoystickLayout::from_reflect(&*layout).unwrap();
+
+        let [joystick] = &layout.joysticks[..] else {
+            panic!("expected one joystick, got {:?}", layout.joysticks);
+        };
+        assert_eq!(joystick.id, "move");
+        assert_eq!(joystick.behaviors.0.len(), 2);
+        assert_eq!(
+            joystick.interaction_region,
+            Some(JoystickInteractionRegion::left_half())
+        );
+        assert_eq!(joystick.style, None);
+    }
+}
@@ -1,33 +1,191 @@
-use std::{hash::Hash, marker::PhantomData};
+use std::{any::TypeId, hash::Hash, marker::PhantomData, sync::Arc};
 
-use bevy::{prelude::*, reflect::GetTypeRegistration, reflect::TypePath};
+use bevy::{
+    ecs::schedule::{InternedScheduleLabel, ScheduleLabel},
+    prelude::*,
+    reflect::GetTypeRegistration,
+    reflect::TypePath,
+};
 
 mod action;
+mod anchor;
 mod behavior;
 mod bundles;
+mod commands;
 mod components;
+mod fixed;
+mod gesture;
+mod id;
+mod layout;
+mod overlap;
+mod pause;
+mod sizing;
 mod systems;
+mod theme;
+mod typed;
 mod utils;
+mod visual;
 
 pub use action::{NoAction, VirtualJoystickAction};
+pub use anchor::{JoystickAnchor, JoystickAnchorPoint, JoystickMargin, JoystickSafeArea};
 pub use behavior::{
-    JoystickDeadZone, JoystickDynamic, JoystickFixed, JoystickFloating, JoystickHorizontalOnly,
-    JoystickInvisible, JoystickVerticalOnly, VirtualJoystickBehavior,
+    JoystickBaseBounds, JoystickBehavior, JoystickBehaviors, JoystickDeadZone, JoystickDynamic,
+    JoystickDynamicFollow, JoystickFade, JoystickFixed, JoystickFloating, JoystickHorizontalOnly,
+    JoystickInvisible, JoystickSmoothing, JoystickSmoothingFilter, JoystickSticky,
+    JoystickVerticalOnly, NoBehavior, VirtualJoystickBehavior,
 };
 pub use bundles::VirtualJoystickBundle;
+pub use commands::JoystickCommandsExt;
 pub use components::{
+    JoystickDisabled, JoystickFadeState, JoystickInteractionRegion, JoystickOutputSpace,
+    JoystickSmoothingState, JoystickStickyState, TouchSample, TouchState,
     VirtualJoystickInteractionArea, VirtualJoystickNode, VirtualJoystickState,
-    VirtualJoystickUIBackground, VirtualJoystickUIKnob,
+    VirtualJoystickUIBackground, VirtualJoystickUIBand, VirtualJoystickUIDirection,
+    VirtualJoystickUIIntensity, VirtualJoystickUIKnob,
+};
+pub use fixed::{FixedJoystickInput, FixedJoystickState};
+pub use gesture::{JoystickGesture, JoystickGestures, VirtualJoystickGestureMessage};
+pub use id::JoystickId;
+#[cfg(feature = "serde")]
+pub use layout::JoystickLayoutLoader;
+pub use layout::{
+    JoystickLayout, JoystickLayoutEntry, JoystickLayoutJoysticks, JoystickLayoutOf,
+    JoystickLayoutRoot,
 };
+pub use overlap::{JoystickOverlapPolicy, JoystickPriority};
+pub use pause::{
+    JoystickPause, joysticks_active, joysticks_paused, pause_joysticks, resume_joysticks,
+};
+pub use sizing::{JoystickDimension, JoystickLength, JoystickSize};
+pub use systems::release_joysticks;
+use systems::release_plugin_joysticks;
 use systems::{
-    update_action, update_behavior, update_behavior_constraints, update_behavior_knob_delta,
-    update_input, update_missing_state, update_send_messages, update_ui,
+    latch_fixed_input, update_action, update_anchors, update_axis_behaviors, update_behavior,
+    update_behavior_constraints, update_behavior_knob_delta, update_dead_zone_behaviors,
+    update_dynamic_behaviors, update_fade_behaviors, update_fixed_behaviors, update_fixed_input,
+    update_floating_behaviors, update_gestures, update_input, update_invisible_behaviors,
+    update_missing_state, update_missing_visual_states, update_send_messages, update_sizes,
+    update_smoothing_behaviors, update_sticky_behaviors, update_theme, update_ui,
+    update_visual_states,
+};
+pub use theme::{JoystickColors, JoystickStyle, JoystickTheme, JoystickThemeStyle};
+pub use typed::JoystickTypedId;
+use typed::{
+    TypedJoystick, forward_gestures, forward_messages, into_runtime_joystick, is_plugin_joystick,
+    latch_typed_fixed_input, remove_runtime_joystick, sync_typed_joysticks,
 };
-pub use utils::create_joystick;
+pub use utils::{
+    ProceduralJoystickStyle, create_joystick, create_joystick_indicators,
+    create_procedural_joystick,
+};
+pub use visual::{
+    JoystickPartVisual, JoystickVisual, JoystickVisualState, JoystickVisualStates,
+    JoystickVisualTransition,
+};
+
+/// Configures a plugin [`App`] for the joysticks of one [`VirtualJoystickID`], given the
+/// schedule of the delta pipeline.
+type AppConfig = Arc<dyn Fn(&mut App, InternedScheduleLabel) + Send + Sync>;
 
-#[derive(Default)]
-pub struct VirtualJoystickPlugin<S> {
+pub struct VirtualJoystickPlugin<S = JoystickId> {
     _marker: PhantomData<S>,
+    schedule: InternedScheduleLabel,
+    run_states: RunStates,
+    configs: Vec<AppConfig>,
+}
+
+impl<S> Default for VirtualJoystickPlugin<S> {
+    fn default() -> Self {
+        Self {
+            _marker: PhantomData,
+            schedule: PreUpdate.intern(),
+            run_states: RunStates::default(),
+            configs: Vec::new(),
+        }
+    }
+}
+
+impl<S: VirtualJoystickID> VirtualJoystickPlugin<S> {
+    /// Run the [`JoystickSystems::UpdateKnobDelta`], [`JoystickSystems::ConstrainKnobDelta`] and
+    /// [`JoystickSystems::SendMessages`] sets in `schedule`.
+    ///
+    /// Defaults to [`PreUpdate`] right after [`JoystickSystems::Input`], so systems in [`Update`]
+    /// see the delta and messages of the current frame. [`JoystickSystems::UpdateUI`] always runs
+    /// in [`PostUpdate`].
+    ///
+    /// Every joystick runs through one pipeline, so every [`VirtualJoystickPlugin`] must use the
+    /// same schedule. Adding plugins with different schedules panics.
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = schedule.intern();
+        self
+    }
+
+    /// Only run the joystick systems while in `state`, see [`Self::run_in_states`].
+    pub fn run_in_state<T: States>(self, state: T) -> Self {
+        self.run_in_states([state])
+    }
+
+    /// Only run the joystick systems while in any of `states`.
+    ///
+    /// Active drags of the joysticks of this [`VirtualJoystickID`] are ended like with
+    /// [`release_joysticks`] on exiting each of `states`. The
+    /// conditions are added to the [`JoystickSystems`] sets, which are shared by every
+    /// [`VirtualJoystickPlugin`], so every plugin must run in the same states. Adding plugins
+    /// with different states panics.
+    pub fn run_in_states<T: States>(mut self, states: impl IntoIterator<Item = T>) -> Self {
+        let states: Vec<T> = states.into_iter().collect();
+        let mut names: Vec<_> = states
+            .iter()
+            .map(|state| format!("{}::{state:?}", std::any::type_name::<T>()))
+            .collect();
+        names.sort();
+        self.run_states.push(names);
+        self.run_states.sort();
+        self.configs.push(Arc::new(move |app: &mut App, schedule| {
+            let in_states = {
+                let states = states.clone();
+                move |state: Option<Res<State<T>>>| {
+                    state.is_some_and(|state| states.contains(state.get()))
+                }
+            };
+            app.configure_sets(PreUpdate, JoystickSystems::Input.run_if(in_states.clone()))
+                .configure_sets(
+                    schedule,
+                    (
+                        JoystickSystems::UpdateKnobDelta,
+                        JoystickSystems::ConstrainKnobDelta,
+                        JoystickSystems::SendMessages,
+                    )
+                        .distributive_run_if(in_states.clone()),
+                )
+                .configure_sets(PostUpdate, JoystickSystems::UpdateUI.run_if(in_states));
+            for state in &states {
+                app.add_systems(OnExit(state.clone()), release_plugin_joysticks::<S>);
+            }
+        }));
+        self
+    }
+
+    /// Scope joysticks to `state`, despawning them with [`DespawnOnExit`] when it is exited.
+    ///
+    /// This applies to every joystick spawned for this [`VirtualJoystickID`], including those
+    /// spawned with [`create_joystick`] and [`create_procedural_joystick`]. Typed joysticks are
+    /// only scoped by the plugin of their own id, see [`JoystickTypedId`].
+    pub fn despawn_on_exit<T: States>(mut self, state: T) -> Self {
+        self.configs.push(Arc::new(move |app: &mut App, _| {
+            let state = state.clone();
+            app.add_observer(
+                move |add: On<Add, VirtualJoystickNode<S>>,
+                      typed: Query<(), With<TypedJoystick>>,
+                      mut cmd: Commands| {
+                    if is_plugin_joystick::<S>(typed.contains(add.entity)) {
+                        cmd.entity(add.entity).insert(DespawnOnExit(state.clone()));
+                    }
+                },
+            );
+        }));
+        self
+    }
 }
 
 #[derive(Message)]
@@ -61,69 +219,283 @@ impl<S: VirtualJoystickID + GetTypeRegistration + bevy::reflect::Typed> Plugin
     for VirtualJoystickPlugin<S>
 {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.register_type::<VirtualJoystickNode<S>>()
-            .register_type::<VirtualJoystickMessageType>()
-            .add_message::<VirtualJoystickMessage<S>>()
-            .add_message::<InputMessage>()
-            .add_systems(
-                PreUpdate,
+        if !app.is_plugin_added::<JoystickCorePlugin>() {
+            app.add_plugins(JoystickCorePlugin {
+                schedule: self.schedule,
+                run_states: self.run_states.clone(),
+            });
+        }
+        // NOTE: The sets are shared, so the conditions of different plugins would all apply.
+        let pipeline = app.world().resource::<JoystickPipeline>();
+        assert!(
+            pipeline.schedule == self.schedule && pipeline.run_states == self.run_states,
+            "`VirtualJoystickPlugin<{}>` runs in {:?} in the states {:?}, but the joystick systems \
+             already run in {:?} in the states {:?}. Every `VirtualJoystickPlugin` must use the \
+             same `in_schedule` and `run_in_states`.",
+            std::any::type_name::<S>(),
+            self.schedule,
+            self.run_states,
+            pipeline.schedule,
+            pipeline.run_states,
+        );
+        let schedule = pipeline.schedule;
+
+        // Typed joysticks run through the runtime pipeline next to their typed node, only their
+        // messages and fixed input are typed, see `JoystickTypedId`.
+        if TypeId::of::<S>() != TypeId::of::<JoystickId>() {
+            app.register_type::<VirtualJoystickNode<S>>()
+                .register_type::<JoystickTypedId<S>>()
+                .add_message::<VirtualJoystickMessage<S>>()
+                .add_message::<VirtualJoystickGestureMessage<S>>()
+                .register_type::<FixedJoystickInput<S>>()
+                .init_resource::<FixedJoystickInput<S>>()
+                .add_observer(into_runtime_joystick::<S>)
+                .add_observer(remove_runtime_joystick::<S>)
+                .add_systems(
+                    PreUpdate,
+                    sync_typed_joysticks::<S>.before(JoystickSystems::Input),
+                )
+                // NOTE: Outside of the `JoystickSystems` sets, so releases on leaving a state
+                // are forwarded too.
+                .add_systems(
+                    schedule,
+                    (forward_messages::<S>, forward_gestures::<S>)
+                        .after(JoystickSystems::SendMessages),
+                )
+                .add_systems(
+                    FixedFirst,
+                    latch_typed_fixed_input::<S>.after(latch_fixed_input),
+                );
+        }
+
+        for config in &self.configs {
+            config(app, schedule);
+        }
+    }
+}
+
+/// Names of the states given to each [`VirtualJoystickPlugin::run_in_states`], sorted so plugins
+/// can be compared.
+type RunStates = Vec<Vec<String>>;
+
+/// Schedule and states of the delta pipeline, decided by the first [`VirtualJoystickPlugin`].
+#[derive(Resource)]
+struct JoystickPipeline {
+    schedule: InternedScheduleLabel,
+    run_states: RunStates,
+}
+
+/// The joystick pipeline, added once by the first [`VirtualJoystickPlugin`].
+///
+/// Every joystick runs through the same systems keyed on its runtime [`JoystickId`], whatever
+/// the [`VirtualJoystickID`] it was spawned with, so the input, behaviors and UI of a joystick
+/// are only processed once and only compiled once.
+struct JoystickCorePlugin {
+    schedule: InternedScheduleLabel,
+    run_states: RunStates,
+}
+
+impl Plugin for JoystickCorePlugin {
+    fn build(&self, app: &mut App) {
+        let schedule = self.schedule;
+        app.insert_resource(JoystickPipeline {
+            schedule,
+            run_states: self.run_states.clone(),
+        })
+        .register_type::<JoystickId>()
+        .register_type::<VirtualJoystickNode>()
+        .register_type::<FixedJoystickInput>()
+        .add_message::<VirtualJoystickMessage>()
+        .add_message::<VirtualJoystickGestureMessage>()
+        .init_resource::<FixedJoystickInput>()
+        .register_type::<VirtualJoystickUIDirection>()
+        .register_type::<VirtualJoystickUIBand>()
+        .register_type::<VirtualJoystickUIIntensity>()
+        .register_type::<VirtualJoystickMessageType>()
+        .register_type::<JoystickFadeState>()
+        .register_type::<JoystickOutputSpace>()
+        .register_type::<JoystickSmoothingState>()
+        .register_type::<JoystickStickyState>()
+        .register_type::<NoBehavior>()
+        .register_type::<JoystickBehavior>()
+        .register_type::<JoystickBehaviors>()
+        .register_type::<JoystickDeadZone>()
+        .register_type::<JoystickHorizontalOnly>()
+        .register_type::<JoystickVerticalOnly>()
+        .register_type::<JoystickInvisible>()
+        .register_type::<JoystickFade>()
+        .register_type::<JoystickFixed>()
+        .register_type::<JoystickFloating>()
+        .register_type::<JoystickDynamic>()
+        .register_type::<JoystickDynamicFollow>()
+        .register_type::<JoystickBaseBounds>()
+        .register_type::<JoystickSticky>()
+        .register_type::<JoystickSmoothing>()
+        .register_type::<JoystickGestures>()
+        .register_type::<JoystickGesture>()
+        .add_message::<InputMessage>()
+        .register_type::<JoystickTheme>()
+        .register_type::<JoystickThemeStyle>()
+        .register_type::<JoystickVisualStates>()
+        .register_type::<JoystickVisualTransition>()
+        .register_type::<JoystickAnchor>()
+        .register_type::<JoystickSafeArea>()
+        .register_type::<JoystickSize>()
+        .register_type::<JoystickDisabled>()
+        .register_type::<JoystickInteractionRegion>()
+        .register_type::<JoystickPause>()
+        .register_type::<JoystickOverlapPolicy>()
+        .register_type::<JoystickPriority>()
+        .init_resource::<JoystickTheme>()
+        .init_resource::<JoystickSafeArea>()
+        .init_resource::<JoystickPause>()
+        .init_resource::<JoystickOverlapPolicy>()
+        .add_systems(
+            PreUpdate,
+            (update_missing_state, update_input)
+                .chain()
+                .in_set(JoystickSystems::Input),
+        )
+        .configure_sets(
+            schedule,
+            (
+                JoystickSystems::UpdateKnobDelta,
+                JoystickSystems::ConstrainKnobDelta,
+                JoystickSystems::SendMessages,
+            )
+                .chain()
+                .after(JoystickSystems::Input),
+        )
+        // NOTE: Trait behaviors run after the behavior components of each stage, so behaviors
+        // pushed onto a joystick with `NoBehavior` see the result of its components.
+        .add_systems(
+            schedule,
+            (
                 (
-                    update_missing_state::<S>,
-                    update_input.after(update_missing_state::<S>),
+                    update_fixed_behaviors,
+                    update_floating_behaviors,
+                    update_dynamic_behaviors,
+                    update_sticky_behaviors,
                 ),
+                update_behavior_knob_delta.run_if(any_trait_behavior),
             )
-            .configure_sets(
-                PostUpdate,
+                .chain()
+                .in_set(JoystickSystems::UpdateKnobDelta),
+        )
+        .add_systems(
+            schedule,
+            (
+                update_dead_zone_behaviors,
+                update_axis_behaviors,
+                update_smoothing_behaviors,
+                update_behavior_constraints.run_if(any_trait_behavior),
+            )
+                .chain()
+                .in_set(JoystickSystems::ConstrainKnobDelta),
+        )
+        .add_systems(
+            schedule,
+            (update_send_messages, update_gestures, update_fixed_input)
+                .in_set(JoystickSystems::SendMessages),
+        )
+        .add_systems(FixedFirst, latch_fixed_input)
+        // NOTE: This only orders anything if the delta pipeline runs in `PostUpdate` too.
+        .configure_sets(
+            PostUpdate,
+            JoystickSystems::UpdateUI.after(JoystickSystems::SendMessages),
+        )
+        .add_systems(
+            PostUpdate,
+            (
                 (
-                    JoystickSystems::UpdateKnobDelta,
-                    JoystickSystems::ConstrainKnobDelta,
-                    JoystickSystems::SendMessages,
-                    JoystickSystems::UpdateUI,
+                    update_invisible_behaviors,
+                    update_fade_behaviors,
+                    update_behavior.run_if(any_trait_behavior),
+                )
+                    .chain(),
+                update_action,
+                update_ui,
+                update_anchors,
+                // NOTE: Visual states apply the opacity of fading joysticks.
+                (
+                    update_theme,
+                    (
+                        update_sizes,
+                        (update_missing_visual_states, update_visual_states)
+                            .chain()
+                            .after(update_behavior),
+                    ),
                 )
                     .chain(),
             )
+                .in_set(JoystickSystems::UpdateUI),
+        );
+    }
+
+    fn finish(&self, app: &mut App) {
+        // NOTE: Layouts need the `AssetPlugin`, which may be added after the joystick plugin.
+        if !app.is_plugin_added::<AssetPlugin>() {
+            return;
+        }
+        app.register_type::<JoystickLayout>()
+            .register_type::<JoystickLayoutRoot>()
+            .register_type::<JoystickLayoutOf>()
+            .register_type::<JoystickLayoutJoysticks>()
+            .init_asset::<JoystickLayout>()
             .add_systems(
-                PostUpdate,
-                update_behavior_knob_delta::<S>.in_set(JoystickSystems::UpdateKnobDelta),
-            )
-            .add_systems(
-                PostUpdate,
-                update_behavior_constraints::<S>.in_set(JoystickSystems::ConstrainKnobDelta),
-            )
-            .add_systems(
-                PostUpdate,
-                update_send_messages::<S>.in_set(JoystickSystems::SendMessages),
-            )
-            .add_systems(
-                PostUpdate,
-                (update_behavior::<S>, update_action::<S>, update_ui)
-                    .in_set(JoystickSystems::UpdateUI),
+                PreUpdate,
+                layout::spawn_layouts.before(JoystickSystems::Input),
             );
+        #[cfg(feature = "serde")]
+        app.init_asset_loader::<JoystickLayoutLoader>();
     }
 }
 
 #[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum JoystickSystems {
+    /// Read mouse and touch input in `PreUpdate`.
+    Input,
     UpdateKnobDelta,
     ConstrainKnobDelta,
     SendMessages,
     UpdateUI,
 }
 
+/// Run condition that is `true` if any joystick uses its [`VirtualJoystickNode::behavior`]
+/// trait object or [`JoystickBehaviors`], see [`NoBehavior`].
+#[allow(clippy::type_complexity)]
+fn any_trait_behavior(
+    joysticks: Query<
+        (),
+        (
+            With<VirtualJoystickNode>,
+            Or<(Without<NoBehavior>, With<JoystickBehaviors>)>,
+        ),
+    >,
+) -> bool {
+    !joysticks.is_empty()
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
 #[reflect]
 pub enum VirtualJoystickMessageType {
     Press,
     Drag,
     Up,
+    /// The drag was aborted instead of released, see [`VirtualJoystickState::just_canceled`].
+    Cancel,
 }
 
 #[derive(Message, Debug)]
-pub struct VirtualJoystickMessage<S: VirtualJoystickID> {
+pub struct VirtualJoystickMessage<S: VirtualJoystickID = JoystickId> {
     id: S,
+    entity: Entity,
     message_type: VirtualJoystickMessageType,
     value: Vec2,
     delta: Vec2,
+    velocity: Vec2,
+    acceleration: Vec2,
+    travel: f32,
 }
 
 impl<S: VirtualJoystickID> VirtualJoystickMessage<S> {
@@ -131,16 +503,55 @@ impl<S: VirtualJoystickID> VirtualJoystickMessage<S> {
     pub fn id(&self) -> S {
         self.id.clone()
     }
+
+    /// The same message with the typed `id`, see [`JoystickTypedId`].
+    pub(crate) fn with_id<T: VirtualJoystickID>(&self, id: T) -> VirtualJoystickMessage<T> {
+        VirtualJoystickMessage {
+            id,
+            entity: self.entity,
+            message_type: self.message_type,
+            value: self.value,
+            delta: self.delta,
+            velocity: self.velocity,
+            acceleration: self.acceleration,
+            travel: self.travel,
+        }
+    }
+
+    /// Get the joystick [`Entity`] of `VirtualJoystickMessage`
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
     /// Raw position of point (Mouse or Touch)
     pub fn value(&self) -> &Vec2 {
         &self.value
     }
 
     /// Delta value ranging from -1 to 1 in each vector (x and y)
+    /// in the [`JoystickOutputSpace`] of the joystick
     pub fn axis(&self) -> &Vec2 {
         &self.delta
     }
 
+    /// Filtered velocity of point (Mouse or Touch) in logical pixels per second
+    ///
+    /// Like [`Self::value`] the y axis points down, on [`VirtualJoystickMessageType::Up`]
+    /// and [`VirtualJoystickMessageType::Cancel`] this is the velocity at release
+    pub fn velocity(&self) -> &Vec2 {
+        &self.velocity
+    }
+
+    /// Filtered acceleration of point (Mouse or Touch) in logical pixels per second squared
+    pub fn acceleration(&self) -> &Vec2 {
+        &self.acceleration
+    }
+
+    /// Distance travelled by point (Mouse or Touch) since the press
+    pub fn travel(&self) -> f32 {
+        self.travel
+    }
+
     /// Return the Type of `VirtualJoystickMessage`
     pub fn get_type(&self) -> VirtualJoystickMessageType {
         self.message_type
@@ -169,3 +580,260 @@ impl<S: VirtualJoystickID> VirtualJoystickMessage<S> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::system::RunSystemOnce, input::InputPlugin, state::app::StatesPlugin, ui::UiScale,
+        window::WindowPlugin,
+    };
+
+    use super::*;
+
+    /// An app with the joystick plugin and a joystick, without rendering.
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            InputPlugin,
+            WindowPlugin::default(),
+            StatesPlugin,
+            VirtualJoystickPlugin::<JoystickId>::default(),
+        ))
+        .init_resource::<UiScale>();
+        app.world_mut().spawn((
+            VirtualJoystickNode::<JoystickId>::default(),
+            JoystickDisabled,
+            children![VirtualJoystickUIBackground, VirtualJoystickUIKnob],
+        ));
+        app
+    }
+
+    #[test]
+    fn plugin_systems_run() {
+        // NOTE: Conflicting queries in a system only panic once it runs.
+        app().update();
+    }
+
+    #[derive(States, Clone, Debug, Default, PartialEq, Eq, Hash)]
+    enum GameState {
+        #[default]
+        Menu,
+        InGame,
+    }
+
+    #[test]
+    fn plugins_in_the_same_states() {
+        let mut app = App::new();
+        app.add_plugins((
+            VirtualJoystickPlugin::<JoystickId>::default()
+                .run_in_states([GameState::InGame, GameState::Menu]),
+            VirtualJoystickPlugin::<String>::default()
+                .run_in_states([GameState::Menu, GameState::InGame]),
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "must use the same `in_schedule` and `run_in_states`")]
+    fn plugins_in_different_states() {
+        let mut app = App::new();
+        app.add_plugins((
+            VirtualJoystickPlugin::<JoystickId>::default().run_in_state(GameState::InGame),
+            VirtualJoystickPlugin::<String>::default().run_in_state(GameState::Menu),
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "must use the same `in_schedule` and `run_in_states`")]
+    fn plugins_in_different_schedules() {
+        let mut app = App::new();
+        app.add_plugins((
+            VirtualJoystickPlugin::<JoystickId>::default(),
+            VirtualJoystickPlugin::<String>::default().in_schedule(Update),
+        ));
+    }
+
+    #[test]
+    fn fade_covers_every_color() {
+        let mut app = app();
+        app.world_mut()
+            .run_system_once(|mut cmd: Commands| {
+                let joystick = create_procedural_joystick(
+                    &mut cmd,
+                    JoystickId::default(),
+                    ProceduralJoystickStyle::default(),
+                    None,
+                    Vec2::splat(75.),
+                    Vec2::splat(150.),
+                    Node::default(),
+                    JoystickFloating,
+                    NoAction,
+                );
+                create_joystick_indicators(&mut cmd, joystick, Color::WHITE, Vec2::splat(150.));
+                cmd.entity(joystick).insert(JoystickFade {
+                    idle_opacity: 0.5,
+                    ..default()
+                });
+            })
+            .unwrap();
+        // NOTE: The second update checks the fade is not applied twice.
+        app.update();
+        app.update();
+
+        let mut borders = app.world_mut().query::<&BorderColor>();
+        // NOTE: Nodes without a border have a transparent one.
+        let alphas: Vec<_> = borders
+            .iter(app.world())
+            .map(|border| border.top.alpha())
+            .filter(|&alpha| alpha > 0.)
+            .collect();
+        // The borders of the knob, base, arrows and indicators have an alpha of 0.8 or 1 before
+        // fading.
+        assert_eq!(alphas.len(), 8);
+        for alpha in alphas {
+            assert!((alpha - 0.4).abs() < 1e-4 || (alpha - 0.5).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn swapping_away_from_fade_restores_colors() {
+        let mut app = app();
+        let joystick = app
+            .world_mut()
+            .run_system_once(|mut cmd: Commands| {
+                let joystick = create_procedural_joystick(
+                    &mut cmd,
+                    JoystickId::default(),
+                    ProceduralJoystickStyle::default(),
+                    None,
+                    Vec2::splat(75.),
+                    Vec2::splat(150.),
+                    Node::default(),
+                    JoystickFloating,
+                    NoAction,
+                );
+                cmd.entity(joystick)
+                    .insert((NoBehavior, JoystickFade::default()));
+                joystick
+            })
+            .unwrap();
+        app.update();
+        app.update();
+        assert_eq!(
+            app.world().get::<Visibility>(joystick),
+            Some(&Visibility::Hidden)
+        );
+
+        app.world_mut()
+            .commands()
+            .entity(joystick)
+            .set_joystick_behavior(JoystickFixed);
+        app.update();
+        app.update();
+
+        let entity = app.world().entity(joystick);
+        assert_eq!(entity.get::<Visibility>(), Some(&Visibility::Inherited));
+        assert!(!entity.contains::<components::JoystickFadeState>());
+        assert!(!entity.contains::<JoystickVisualStates>());
+        let mut part_opacities = app.world_mut().query::<&components::JoystickPartOpacity>();
+        assert_eq!(part_opacities.iter(app.world()).count(), 0);
+
+        let mut borders = app.world_mut().query::<&BorderColor>();
+        let alphas: Vec<_> = borders
+            .iter(app.world())
+            .map(|border| border.top.alpha())
+            .filter(|&alpha| alpha > 0.)
+            .collect();
+        // The borders of the knob, base and arrows are back to an alpha of 0.8 or 1.
+        assert_eq!(alphas.len(), 6);
+        for alpha in alphas {
+            assert!((alpha - 0.8).abs() < 1e-3 || (alpha - 1.).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn swapping_away_from_smoothing_places_the_base() {
+        let mut app = app();
+        let joystick = app
+            .world_mut()
+            .spawn((
+                VirtualJoystickNode::<JoystickId>::default(),
+                NoBehavior,
+                JoystickSmoothing::default(),
+                Node::default(),
+                children![
+                    (VirtualJoystickUIBackground, Node::default()),
+                    (VirtualJoystickUIKnob, Node::default()),
+                ],
+            ))
+            .id();
+        app.update();
+
+        app.world_mut()
+            .commands()
+            .entity(joystick)
+            .set_joystick_behavior(JoystickFixed);
+        app.update();
+        assert!(
+            !app.world()
+                .entity(joystick)
+                .contains::<components::JoystickSmoothingState>()
+        );
+
+        // A smoothing state left behind is ignored without smoothing.
+        app.world_mut()
+            .entity_mut(joystick)
+            .insert(components::JoystickSmoothingState {
+                base_offset: Vec2::new(40., 0.),
+                ..default()
+            });
+        app.update();
+        let mut bases = app
+            .world_mut()
+            .query_filtered::<(&ChildOf, &Node), With<VirtualJoystickUIBackground>>();
+        let (_, base) = bases
+            .iter(app.world())
+            .find(|(child_of, _)| child_of.parent() == joystick)
+            .unwrap();
+        assert_eq!(base.left, Val::Px(0.));
+    }
+
+    #[test]
+    fn typed_joysticks_keep_their_typed_node() {
+        let mut app = app();
+        app.add_plugins(VirtualJoystickPlugin::<String>::default());
+        let entity = app
+            .world_mut()
+            .spawn(VirtualJoystickNode::<String>::default().with_id("move".to_string()))
+            .id();
+        app.update();
+
+        let runtime_id = |app: &App| {
+            app.world()
+                .get::<VirtualJoystickNode>(entity)
+                .unwrap()
+                .id
+                .clone()
+        };
+        assert_eq!(runtime_id(&app), "move");
+        app.world_mut()
+            .get_mut::<VirtualJoystickNode<String>>(entity)
+            .unwrap()
+            .id = "look".to_string();
+        app.update();
+        assert_eq!(runtime_id(&app), "look");
+        assert_eq!(
+            app.world()
+                .get::<JoystickTypedId<String>>(entity)
+                .unwrap()
+                .0,
+            "look"
+        );
+
+        app.world_mut()
+            .entity_mut(entity)
+            .remove::<VirtualJoystickNode<String>>();
+        app.update();
+        assert!(app.world().get::<VirtualJoystickNode>(entity).is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use std::cmp::Ordering;
+
+use bevy::{
+    ecs::{
+        component::Component, entity::Entity, reflect::ReflectComponent, reflect::ReflectResource,
+        resource::Resource,
+    },
+    reflect::{Reflect, std_traits::ReflectDefault},
+};
+
+/// How a press is assigned when the interaction areas of several joysticks overlap.
+///
+/// Every touch, and the mouse, drives at most one joystick. Remaining ties go to the joystick
+/// with the nearest base center, then to the lowest [`Entity`].
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default)]
+pub enum JoystickOverlapPolicy {
+    /// The joystick whose base center is nearest to the press.
+    #[default]
+    NearestBase,
+    /// The joystick with the highest [`JoystickPriority`].
+    Priority,
+    /// The joystick drawn on top of the others.
+    Topmost,
+}
+
+/// Priority of a joystick for [`JoystickOverlapPolicy::Priority`], joysticks without it have a
+/// priority of zero.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickPriority(pub i32);
+
+/// A joystick whose interaction area contains a press.
+pub(crate) struct OverlapCandidate {
+    pub(crate) entity: Entity,
+    /// Distance from the press to the center of the base in logical pixels.
+    pub(crate) distance: f32,
+    pub(crate) priority: JoystickPriority,
+    pub(crate) stack_index: u32,
+}
+
+impl JoystickOverlapPolicy {
+    /// The joystick among `candidates` a press is assigned to.
+    pub(crate) fn resolve<'a>(
+        self,
+        candidates: impl IntoIterator<Item = &'a OverlapCandidate>,
+    ) -> Option<Entity> {
+        candidates
+            .into_iter()
+            .min_by(|a, b| {
+                let order = match self {
+                    Self::NearestBase => Ordering::Equal,
+                    Self::Priority => b.priority.cmp(&a.priority),
+                    Self::Topmost => b.stack_index.cmp(&a.stack_index),
+                };
+                order
+                    .then(a.distance.total_cmp(&b.distance))
+                    .then(a.entity.cmp(&b.entity))
+            })
+            .map(|candidate| candidate.entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(index: u32, distance: f32, priority: i32, stack_index: u32) -> OverlapCandidate {
+        OverlapCandidate {
+            entity: Entity::from_raw_u32(index).unwrap(),
+            distance,
+            priority: JoystickPriority(priority),
+            stack_index,
+        }
+    }
+
+    fn resolve(policy: JoystickOverlapPolicy, candidates: &[OverlapCandidate]) -> Option<u32> {
+        policy.resolve(candidates).map(Entity::index_u32)
+    }
+
+    #[test]
+    fn no_candidates() {
+        assert_eq!(resolve(JoystickOverlapPolicy::NearestBase, &[]), None);
+    }
+
+    #[test]
+    fn nearest_base() {
+        let candidates = [candidate(1, 30., 5, 5), candidate(2, 10., 0, 0)];
+        assert_eq!(
+            resolve(JoystickOverlapPolicy::NearestBase, &candidates),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn priority() {
+        let candidates = [candidate(1, 10., 0, 5), candidate(2, 30., 1, 0)];
+        assert_eq!(
+            resolve(JoystickOverlapPolicy::Priority, &candidates),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn topmost() {
+        let candidates = [candidate(1, 10., 5, 0), candidate(2, 30., 0, 1)];
+        assert_eq!(
+            resolve(JoystickOverlapPolicy::Topmost, &candidates),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn ties_go_to_nearest_base_then_lowest_entity() {
+        let candidates = [
+            candidate(3, 20., 1, 1),
+            candidate(2, 10., 1, 1),
+            candidate(1, 10., 1, 1),
+            candidate(0, 5., 0, 0),
+        ];
+        // The order of entities is not the order of their indices.
+        let lowest = candidates[1].entity.min(candidates[2].entity).index_u32();
+        assert_eq!(
+            resolve(JoystickOverlapPolicy::Priority, &candidates),
+            Some(lowest)
+        );
+        assert_eq!(
+            resolve(JoystickOverlapPolicy::Topmost, &candidates),
+            Some(lowest)
+        );
+        assert_eq!(
+            resolve(JoystickOverlapPolicy::NearestBase, &candidates[..3]),
+            Some(lowest)
+        );
+    }
+}
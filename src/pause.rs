@@ -0,0 +1,37 @@
+use bevy::{
+    ecs::{
+        reflect::ReflectResource,
+        resource::Resource,
+        system::{Res, ResMut},
+    },
+    reflect::{Reflect, std_traits::ReflectDefault},
+};
+
+/// Suspend the input of every joystick, like a [`crate::JoystickDisabled`] on all of them.
+///
+/// Active touches are released with a [`crate::VirtualJoystickMessageType::Up`] when pausing.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource, Default)]
+pub struct JoystickPause {
+    pub paused: bool,
+}
+
+/// Run condition that is `true` while joysticks are paused with [`JoystickPause`].
+pub fn joysticks_paused(pause: Res<JoystickPause>) -> bool {
+    pause.paused
+}
+
+/// Run condition that is `true` while joysticks are not paused with [`JoystickPause`].
+pub fn joysticks_active(pause: Res<JoystickPause>) -> bool {
+    !pause.paused
+}
+
+/// System pausing every joystick, for example on entering a menu state.
+pub fn pause_joysticks(mut pause: ResMut<JoystickPause>) {
+    pause.paused = true;
+}
+
+/// System resuming every joystick paused with [`pause_joysticks`].
+pub fn resume_joysticks(mut pause: ResMut<JoystickPause>) {
+    pause.paused = false;
+}
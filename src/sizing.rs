@@ -0,0 +1,120 @@
+use bevy::{
+    ecs::{component::Component, reflect::ReflectComponent},
+    reflect::{Reflect, std_traits::ReflectDefault},
+    window::Window,
+};
+
+/// A length that scales with the screen instead of being a raw [`bevy::ui::Val::Px`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+pub enum JoystickLength {
+    /// Logical pixels.
+    Px(f32),
+    /// Physical size on screen in millimeters, see [`JoystickLength::pixels_per_millimeter`].
+    Millimeters(f32),
+    /// Percentage of the short side of the window.
+    ShortSide(f32),
+}
+
+impl JoystickLength {
+    /// Physical pixels per inch of a screen with a scale factor of one on this platform.
+    pub const REFERENCE_DPI: f32 = if cfg!(target_os = "android") {
+        160.
+    } else if cfg!(target_os = "ios") {
+        163.
+    } else {
+        96.
+    };
+
+    /// Logical pixels per millimeter on `window`.
+    ///
+    /// Platforms pick the base scale factor of a window from the density of its screen, so it
+    /// spans `base_scale_factor * REFERENCE_DPI` physical pixels per inch. Logical pixels are
+    /// then [`Window::scale_factor`] physical pixels, which differs with a scale factor override.
+    /// Without a window this is [`Self::REFERENCE_DPI`] per inch.
+    pub fn pixels_per_millimeter(window: Option<&Window>) -> f32 {
+        let scale = window.map_or(1., |window| {
+            window.resolution.base_scale_factor() / window.scale_factor()
+        });
+        Self::REFERENCE_DPI * scale / 25.4
+    }
+
+    /// Resolve the length in logical pixels on `window`, see [`Self::pixels_per_millimeter`].
+    pub fn resolve(self, window: Option<&Window>) -> f32 {
+        match self {
+            Self::Px(px) => px,
+            Self::Millimeters(mm) => mm * Self::pixels_per_millimeter(window),
+            Self::ShortSide(percent) => {
+                percent / 100. * window.map_or(0., |window| window.size().min_element())
+            }
+        }
+    }
+}
+
+impl Default for JoystickLength {
+    fn default() -> Self {
+        Self::Px(150.)
+    }
+}
+
+/// A [`JoystickLength`] clamped between optional bounds in logical pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+pub struct JoystickDimension {
+    pub length: JoystickLength,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+impl JoystickDimension {
+    pub fn px(px: f32) -> Self {
+        JoystickLength::Px(px).into()
+    }
+
+    pub fn millimeters(mm: f32) -> Self {
+        JoystickLength::Millimeters(mm).into()
+    }
+
+    pub fn short_side(percent: f32) -> Self {
+        JoystickLength::ShortSide(percent).into()
+    }
+
+    pub fn with_min(mut self, min: f32) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub fn with_max(mut self, max: f32) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Resolve the dimension in logical pixels, see [`JoystickLength::resolve`].
+    pub fn resolve(self, window: Option<&Window>) -> f32 {
+        let length = self.length.resolve(window);
+        let length = self.min.map_or(length, |min| length.max(min));
+        self.max.map_or(length, |max| length.min(max))
+    }
+}
+
+impl From<JoystickLength> for JoystickDimension {
+    fn from(length: JoystickLength) -> Self {
+        Self {
+            length,
+            min: None,
+            max: None,
+        }
+    }
+}
+
+/// Resolution and DPI independent sizes of a joystick, its background and its knob.
+///
+/// Each part set here is resized to a square whenever the window is resized or rotated,
+/// overriding the sizes of a [`crate::JoystickStyle`]. Parts left as `None` are kept as spawned.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickSize {
+    pub joystick: Option<JoystickDimension>,
+    pub background: Option<JoystickDimension>,
+    pub knob: Option<JoystickDimension>,
+}
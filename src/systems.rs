@@ -1,26 +1,54 @@
-use std::sync::Arc;
+use std::{f32::consts::FRAC_PI_2, sync::Arc};
 
 use bevy::{
+    color::{Alpha, Color},
     ecs::{
+        change_detection::{DetectChanges, DetectChangesMut, Ref},
         entity::Entity,
-        message::MessageWriter,
-        query::With,
-        system::{Query, Res, Single},
+        message::{MessageReader, MessageWriter},
+        query::{Has, Or, QueryItem, QueryState, With},
+        system::{Commands, Local, ParallelCommands, Query, Res, ResMut, Single, SystemParam},
         world::World,
     },
     input::{ButtonInput, mouse::MouseButton, touch::Touches},
-    math::{Rect, Vec2},
-    prelude::Children,
-    ui::{ComputedNode, Node, PositionType, UiGlobalTransform, UiScale, Val},
-    window::{PrimaryWindow, Window},
+    math::{Rect, Rot2, Vec2, curve::Curve},
+    prelude::{ChildOf, Children, Visibility},
+    time::Time,
+    ui::{
+        BackgroundColor, BorderColor, ComputedNode, Node, Outline, PositionType, UiGlobalTransform,
+        UiScale, UiTransform, Val, widget::ImageNode,
+    },
+    window::{AppLifecycle, PrimaryWindow, Window},
 };
 
 use crate::{
-    VirtualJoystickID, VirtualJoystickMessage, VirtualJoystickMessageType, VirtualJoystickNode,
+    JoystickDeadZone, JoystickDynamic, JoystickDynamicFollow, JoystickFade, JoystickFixed,
+    JoystickFloating, JoystickHorizontalOnly, JoystickInvisible, JoystickSmoothing, JoystickSticky,
+    JoystickVerticalOnly, VirtualJoystickID, VirtualJoystickMessage, VirtualJoystickMessageType,
+    VirtualJoystickNode,
+    anchor::{JoystickAnchor, JoystickSafeArea},
+    behavior::{
+        JoystickBaseBounds, JoystickBehaviors, NoBehavior, NodeFrame, PartColorQuery,
+        VirtualJoystickBehavior, current_visual_states, release_behavior_state,
+    },
     components::{
-        TouchState, VirtualJoystickInteractionArea, VirtualJoystickState,
-        VirtualJoystickUIBackground, VirtualJoystickUIKnob,
+        InteractionArea, JoystickDisabled, JoystickFadeState, JoystickFadeVisualStates,
+        JoystickHiddenByBehavior, JoystickInteractionRegion, JoystickOutputSpace,
+        JoystickPartOpacity, JoystickSmoothedByBehavior, JoystickSmoothingState,
+        JoystickStickyState, TouchState, VirtualJoystickInteractionArea, VirtualJoystickState,
+        VirtualJoystickUIBackground, VirtualJoystickUIBand, VirtualJoystickUIDirection,
+        VirtualJoystickUIIntensity, VirtualJoystickUIKnob,
     },
+    fixed::FixedJoystickInput,
+    gesture::{
+        JoystickGesture, JoystickGestureState, JoystickGestures, VirtualJoystickGestureMessage,
+    },
+    overlap::{JoystickOverlapPolicy, JoystickPriority, OverlapCandidate},
+    pause::JoystickPause,
+    sizing::{JoystickDimension, JoystickSize},
+    theme::{JoystickTheme, JoystickThemeStyle},
+    typed::{TypedJoystick, is_plugin_joystick},
+    visual::{JoystickVisualState, JoystickVisualStates, JoystickVisualTransition},
 };
 use bevy::ecs::query::Without;
 
@@ -32,8 +60,8 @@ enum DragAction {
 }
 
 /// Add missing [`VirtualJoystickState`]s for [`Entity`]s with [`VirtualJoystickNode`]
-pub fn update_missing_state<S: VirtualJoystickID>(world: &mut World) {
-    let mut joysticks = world.query_filtered::<Entity, With<VirtualJoystickNode<S>>>();
+pub fn update_missing_state(world: &mut World) {
+    let mut joysticks = world.query_filtered::<Entity, With<VirtualJoystickNode>>();
     let joysticks: Vec<_> = joysticks.iter(world).collect();
 
     for entity in joysticks {
@@ -46,130 +74,625 @@ pub fn update_missing_state<S: VirtualJoystickID>(world: &mut World) {
 }
 
 /// Update stored inputs in [`VirtualJoystickState`].
+///
+/// Joysticks keep their touch until it is released, new presses are then assigned to free
+/// joysticks following the [`JoystickOverlapPolicy`].
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn update_input(
     window: Single<&Window, With<PrimaryWindow>>,
-    joystick_query: Query<(
+    mut joystick_query: Query<(
         Entity,
         &ComputedNode,
         &UiGlobalTransform,
         &mut VirtualJoystickState,
+        Has<JoystickDisabled>,
+        Option<&JoystickInteractionRegion>,
+        Option<&JoystickPriority>,
     )>,
     children_query: Query<&Children>,
-    interaction_area_query: Query<
-        (&ComputedNode, &UiGlobalTransform),
-        With<VirtualJoystickInteractionArea>,
-    >,
+    interaction_area_query: Query<(), With<VirtualJoystickInteractionArea>>,
+    node_query: Query<(&ComputedNode, &UiGlobalTransform)>,
+    base_query: BaseFrameQuery,
     mouse_buttons: Res<ButtonInput<MouseButton>>,
     touches: Res<Touches>,
     ui_scale: Res<UiScale>,
+    time: Res<Time>,
+    pause: Res<JoystickPause>,
+    overlap_policy: Res<JoystickOverlapPolicy>,
+    mut lifecycle: MessageReader<AppLifecycle>,
 ) {
-    for (entity, node, transform, mut state) in joystick_query {
-        state.just_released = false;
+    let suspended = matches!(
+        lifecycle.read().last(),
+        Some(AppLifecycle::WillSuspend | AppLifecycle::Suspended)
+    );
 
-        // Get interaction rect or fallback to default calculated with `transform` from `joystick_query`.
-        let interaction_rect = if let Some(children) = children_query.get(entity).into_iter().next()
-            && let Some((node, interaction_transform)) = children
-                .iter()
-                .find_map(|&child| interaction_area_query.get(child).ok())
-        {
-            node_rect(node, interaction_transform.translation, ui_scale.0)
-        } else {
-            node_rect(node, transform.translation, ui_scale.0)
-        };
+    // Touches and mouse held by a joystick, and the free joysticks with their interaction area.
+    let mut claimed_touches = Vec::new();
+    let mut claimed_mouse = false;
+    let mut free_joysticks = Vec::new();
 
-        if let Some(touch_state) = &mut state.touch_state {
-            touch_state.just_pressed = false;
+    for (entity, node, transform, mut state, disabled, region, priority) in &mut joystick_query {
+        state.just_released = false;
+        state.just_canceled = false;
 
-            // Continue and clear touch state if the left mouse button has just been released or the touch
-            // input has just been released.
-            if (touch_state.is_mouse && mouse_buttons.just_released(MouseButton::Left))
-                || touches.just_released(touch_state.id)
-            {
-                state.touch_state = None;
+        // Release the touch of disabled joysticks and ignore their input.
+        if disabled || pause.paused {
+            if state.touch_state.take().is_some() {
                 state.just_released = true;
-                continue;
+            } else {
+                state.velocity = Vec2::ZERO;
+                state.acceleration = Vec2::ZERO;
+                state.travel = 0.;
             }
+            continue;
+        }
 
-            // Continue and set new current from touch input
-            if let Some(touch) = touches.get_pressed(touch_state.id) {
-                touch_state.set_new_current(touch.position());
-                continue;
-            }
-            // Set new current position from cursor position if using mouse.
-            if touch_state.is_mouse
-                && let Some(current) = window.cursor_position()
-            {
-                touch_state.set_new_current(current);
-            }
-        } else if let Some(touch) = touches
-            .iter()
-            .find(|touch| interaction_rect.contains(touch.position()))
+        let Some(touch_state) = &mut state.touch_state else {
+            let area = InteractionArea::resolve(
+                entity,
+                region.copied(),
+                Some(window.size()),
+                children_query.get(entity).ok(),
+                |area| node_query.contains(area),
+                |area| interaction_area_query.contains(area),
+            );
+            let base_center = children_query
+                .get(entity)
+                .ok()
+                .and_then(|children| base_frame(children, &base_query, ui_scale.0))
+                .unwrap_or_else(|| NodeFrame::new(node, transform, ui_scale.0))
+                .center();
+            free_joysticks.push((
+                area,
+                OverlapCandidate {
+                    entity,
+                    distance: 0.,
+                    priority: priority.copied().unwrap_or_default(),
+                    stack_index: node.stack_index(),
+                },
+                base_center,
+            ));
+            continue;
+        };
+
+        touch_state.just_pressed = false;
+        touch_state.duration += time.delta_secs();
+
+        // Continue and clear touch state if the left mouse button has just been released or the touch
+        // input has just been released.
+        if (touch_state.is_mouse && mouse_buttons.just_released(MouseButton::Left))
+            || touches.just_released(touch_state.id)
         {
-            // If using touch and within the interaction rect, set `state.touch_state` to touch input.
-            state.touch_state = Some(TouchState::from_touch_pos(touch.id(), touch.position()));
-        } else if mouse_buttons.just_pressed(MouseButton::Left)
-            && let Some(mouse_pos) = window.cursor_position()
-            && interaction_rect.contains(mouse_pos)
+            state.touch_state = None;
+            state.just_released = true;
+            continue;
+        }
+
+        // Continue and cancel touch state if the touch was canceled or is gone, the mouse left
+        // the window, the window lost focus or the app is being suspended.
+        let lost = if touch_state.is_mouse {
+            window.cursor_position().is_none()
+        } else {
+            touches.just_canceled(touch_state.id) || touches.get_pressed(touch_state.id).is_none()
+        };
+        if lost || suspended || !window.focused {
+            state.touch_state = None;
+            state.just_released = true;
+            state.just_canceled = true;
+            continue;
+        }
+
+        // Set new current from touch input, or from cursor position if using mouse.
+        if touch_state.is_mouse {
+            claimed_mouse = true;
+        } else {
+            claimed_touches.push(touch_state.id);
+        }
+        if let Some(touch) = touches.get_pressed(touch_state.id) {
+            touch_state.set_new_current(touch.position());
+        } else if touch_state.is_mouse
+            && let Some(current) = window.cursor_position()
         {
-            // If the left mouse button has just been pressed within the interaction rect,
-            // set `state.touch_state` to mouse input.
-            state.touch_state = Some(TouchState::from_mouse_pos(0, mouse_pos));
+            touch_state.set_new_current(current);
+        }
+        touch_state.push_sample();
+        update_kinematics(&mut state);
+    }
+
+    // Assign each free touch within an interaction rect to at most one joystick, in order of the
+    // touch ids so overlaps resolve the same way every frame.
+    let mut presses: Vec<_> = touches
+        .iter()
+        .filter(|touch| !claimed_touches.contains(&touch.id()))
+        .map(|touch| TouchState::from_touch_pos(touch.id(), touch.position()))
+        .collect();
+    presses.sort_by_key(|touch_state| touch_state.id);
+    // If the left mouse button has just been pressed within an interaction rect, use mouse input.
+    if !claimed_mouse
+        && mouse_buttons.just_pressed(MouseButton::Left)
+        && let Some(mouse_pos) = window.cursor_position()
+    {
+        presses.push(TouchState::from_mouse_pos(0, mouse_pos));
+    }
+    // Ignore new presses while the window is unfocused or the app is being suspended.
+    if !window.focused || suspended {
+        presses.clear();
+    }
+    for press in presses {
+        let candidates: Vec<_> = free_joysticks
+            .iter_mut()
+            .filter(|(area, ..)| match *area {
+                InteractionArea::Screen(rect) => rect.contains(press.start),
+                InteractionArea::Node(area) => {
+                    node_query.get(area).is_ok_and(|(node, transform)| {
+                        node_contains(node, transform, press.start, ui_scale.0)
+                    })
+                }
+            })
+            .map(|(_, candidate, base_center)| {
+                candidate.distance = press.start.distance(*base_center);
+                &*candidate
+            })
+            .collect();
+        let Some(entity) = overlap_policy.resolve(candidates) else {
+            continue;
+        };
+        free_joysticks.retain(|(_, candidate, _)| candidate.entity != entity);
+        if let Ok((_, _, _, mut state, ..)) = joystick_query.get_mut(entity) {
+            state.touch_state = Some(press);
+            update_kinematics(&mut state);
+        }
+    }
+
+    // Expose the pointer kinematics of the joysticks left free, clearing those of a release.
+    for (_, candidate, _) in free_joysticks {
+        if let Ok((_, _, _, mut state, ..)) = joystick_query.get_mut(candidate.entity) {
+            update_kinematics(&mut state);
         }
     }
 }
 
-/// Update behavior knob delta by calling [`crate::behavior::VirtualJoystickBehavior::update_at_delta_stage`] for each joystick entity.
-pub fn update_behavior_knob_delta<S: VirtualJoystickID>(world: &mut World) {
-    let mut joysticks = world.query_filtered::<Entity, With<VirtualJoystickNode<S>>>();
-    let joysticks: Vec<_> = joysticks.iter(world).collect();
+/// Expose the pointer kinematics in [`VirtualJoystickState`], these are kept for the frame of a
+/// release.
+fn update_kinematics(state: &mut VirtualJoystickState) {
+    let (velocity, acceleration, travel) =
+        state
+            .touch_state
+            .as_ref()
+            .map_or((Vec2::ZERO, Vec2::ZERO, 0.), |touch_state| {
+                (
+                    touch_state.velocity,
+                    touch_state.acceleration,
+                    touch_state.travel,
+                )
+            });
+    state.velocity = velocity;
+    state.acceleration = acceleration;
+    state.travel = travel;
+}
 
-    for entity in joysticks {
-        let behavior = {
-            let Some(virtual_joystick_node) = world.get::<VirtualJoystickNode<S>>(entity) else {
-                continue;
-            };
-            Arc::clone(&virtual_joystick_node.behavior)
-        };
+/// Joysticks with trait behaviors and their [`VirtualJoystickNode::behavior`], see
+/// [`run_trait_behaviors`].
+type TraitBehaviorQuery = QueryState<(
+    Entity,
+    &'static VirtualJoystickNode,
+    Has<NoBehavior>,
+    Has<JoystickBehaviors>,
+)>;
+
+/// Buffer of the joysticks to run, kept between frames to not allocate.
+type TraitBehaviorBuffer = Vec<(Entity, Option<Arc<dyn VirtualJoystickBehavior>>)>;
+
+/// Update behavior knob delta by calling [`crate::behavior::VirtualJoystickBehavior::update_at_delta_stage`] for each joystick entity.
+pub fn update_behavior_knob_delta(
+    world: &mut World,
+    query: &mut TraitBehaviorQuery,
+    mut joysticks: Local<TraitBehaviorBuffer>,
+) {
+    run_trait_behaviors(world, query, &mut joysticks, |behavior, world, entity| {
         behavior.update_at_delta_stage(world, entity);
-    }
+    });
 }
 
 /// Update behavior constraints by calling [`crate::behavior::VirtualJoystickBehavior::update_at_constraint_stage`] for each joystick entity.
-pub fn update_behavior_constraints<S: VirtualJoystickID>(world: &mut World) {
-    let mut joysticks = world.query_filtered::<Entity, With<VirtualJoystickNode<S>>>();
-    let joysticks: Vec<_> = joysticks.iter(world).collect();
+pub fn update_behavior_constraints(
+    world: &mut World,
+    query: &mut TraitBehaviorQuery,
+    mut joysticks: Local<TraitBehaviorBuffer>,
+) {
+    run_trait_behaviors(world, query, &mut joysticks, |behavior, world, entity| {
+        behavior.update_at_constraint_stage(world, entity);
+    });
+}
 
-    for entity in joysticks {
-        let behavior = {
-            let Some(virtual_joystick_node) = world.get::<VirtualJoystickNode<S>>(entity) else {
-                continue;
-            };
-            Arc::clone(&virtual_joystick_node.behavior)
+/// Update behavior by calling [`crate::behavior::VirtualJoystickBehavior::update`] for each joystick entity.
+pub fn update_behavior(
+    world: &mut World,
+    query: &mut TraitBehaviorQuery,
+    mut joysticks: Local<TraitBehaviorBuffer>,
+) {
+    run_trait_behaviors(world, query, &mut joysticks, |behavior, world, entity| {
+        behavior.update(world, entity);
+    });
+}
+
+/// Run `stage` of the [`VirtualJoystickNode::behavior`] of each joystick, followed by its
+/// [`JoystickBehaviors`].
+///
+/// Joysticks with [`NoBehavior`] only run their [`JoystickBehaviors`].
+fn run_trait_behaviors(
+    world: &mut World,
+    query: &mut TraitBehaviorQuery,
+    joysticks: &mut TraitBehaviorBuffer,
+    stage: impl Fn(&dyn VirtualJoystickBehavior, &mut World, Entity),
+) {
+    joysticks.extend(
+        query
+            .iter(world)
+            .filter(|&(_, _, no_behavior, has_behaviors)| !no_behavior || has_behaviors)
+            .map(|(entity, node, no_behavior, _)| {
+                (entity, (!no_behavior).then(|| Arc::clone(&node.behavior)))
+            }),
+    );
+
+    for (entity, behavior) in joysticks.drain(..) {
+        if let Some(behavior) = behavior {
+            stage(&*behavior, world, entity);
+        }
+
+        // NOTE: The stack is moved out while it runs instead of cloned, behaviors get the world.
+        let Some(behaviors) = world
+            .get_mut::<JoystickBehaviors>(entity)
+            .map(|mut behaviors| std::mem::take(&mut behaviors.bypass_change_detection().0))
+        else {
+            continue;
         };
-        behavior.update_at_constraint_stage(world, entity);
+        for behavior in &behaviors {
+            stage(behavior.as_dyn(), world, entity);
+        }
+        // Put the stack back, unless a behavior replaced it.
+        if let Some(mut current) = world.get_mut::<JoystickBehaviors>(entity)
+            && current.0.is_empty()
+        {
+            current.bypass_change_detection().0 = behaviors;
+        }
     }
 }
 
-/// Update behavior by calling [`crate::behavior::VirtualJoystickBehavior::update`] for each joystick entity.
-pub fn update_behavior<S: VirtualJoystickID>(world: &mut World) {
-    let mut joysticks = world.query_filtered::<Entity, With<VirtualJoystickNode<S>>>();
-    let joysticks: Vec<_> = joysticks.iter(world).collect();
+/// Query for the [`NodeFrame`] of joystick bases.
+type BaseFrameQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static ComputedNode, &'static UiGlobalTransform),
+    With<VirtualJoystickUIBackground>,
+>;
 
-    for entity in joysticks {
-        let behavior = {
-            let Some(virtual_joystick_node) = world.get::<VirtualJoystickNode<S>>(entity) else {
-                continue;
+/// The [`NodeFrame`] of the base among the `children` of a joystick.
+fn base_frame(
+    children: &Children,
+    base_query: &BaseFrameQuery,
+    ui_scale: f32,
+) -> Option<NodeFrame> {
+    children
+        .iter()
+        .find_map(|&child| base_query.get(child).ok())
+        .map(|(node, transform)| NodeFrame::new(node, transform, ui_scale))
+}
+
+/// Update knob delta of joysticks with a [`JoystickFixed`] component.
+///
+/// Ignored with [`JoystickFloating`], [`JoystickDynamic`] or [`JoystickSticky`], see
+/// [`NoBehavior`].
+#[allow(clippy::type_complexity)]
+pub fn update_fixed_behaviors(
+    mut joystick_query: Query<
+        (&JoystickFixed, &mut VirtualJoystickState, &Children),
+        (
+            With<VirtualJoystickNode>,
+            Without<JoystickFloating>,
+            Without<JoystickDynamic>,
+            Without<JoystickSticky>,
+        ),
+    >,
+    base_query: BaseFrameQuery,
+    ui_scale: Res<UiScale>,
+) {
+    joystick_query
+        .par_iter_mut()
+        .for_each(|(behavior, mut state, children)| {
+            if let Some(base_frame) = base_frame(children, &base_query, ui_scale.0) {
+                behavior.knob_delta(&mut state, base_frame);
+            }
+        });
+}
+
+/// Update knob delta and base offset of joysticks with a [`JoystickFloating`] component.
+///
+/// Ignored with [`JoystickDynamic`] or [`JoystickSticky`], see [`NoBehavior`].
+#[allow(clippy::type_complexity)]
+pub fn update_floating_behaviors(
+    mut joystick_query: Query<
+        (
+            Entity,
+            &JoystickFloating,
+            &mut VirtualJoystickState,
+            &ComputedNode,
+            &UiGlobalTransform,
+            &Children,
+            Option<&JoystickBaseBounds>,
+        ),
+        (
+            With<VirtualJoystickNode>,
+            Without<JoystickDynamic>,
+            Without<JoystickSticky>,
+        ),
+    >,
+    base_query: BaseFrameQuery,
+    bounds_query: BaseBoundsQuery,
+    ui_scale: Res<UiScale>,
+) {
+    joystick_query.par_iter_mut().for_each(
+        |(entity, behavior, mut state, node, transform, children, bounds)| {
+            let Some(base_frame) = base_frame(children, &base_query, ui_scale.0) else {
+                return;
             };
-            Arc::clone(&virtual_joystick_node.behavior)
+            let frame = NodeFrame::new(node, transform, ui_scale.0);
+            let bounds = bounds.copied().unwrap_or(JoystickBaseBounds::Unbounded);
+            let bounds = bounds_query.frame(entity, children, frame, bounds);
+            behavior.knob_delta(&mut state, frame, base_frame, bounds);
+        },
+    );
+}
+
+/// Update knob delta and base offset of joysticks with a [`JoystickDynamic`] component.
+///
+/// Ignored with [`JoystickSticky`], see [`NoBehavior`].
+#[allow(clippy::type_complexity)]
+pub fn update_dynamic_behaviors(
+    mut joystick_query: Query<
+        (
+            Entity,
+            &JoystickDynamic,
+            &mut VirtualJoystickState,
+            &ComputedNode,
+            &UiGlobalTransform,
+            &Children,
+            Option<&JoystickBaseBounds>,
+            Option<&JoystickDynamicFollow>,
+        ),
+        (With<VirtualJoystickNode>, Without<JoystickSticky>),
+    >,
+    base_query: BaseFrameQuery,
+    bounds_query: BaseBoundsQuery,
+    ui_scale: Res<UiScale>,
+    time: Res<Time>,
+) {
+    joystick_query.par_iter_mut().for_each(
+        |(entity, behavior, mut state, node, transform, children, bounds, follow)| {
+            let Some(base_frame) = base_frame(children, &base_query, ui_scale.0) else {
+                return;
+            };
+            let frame = NodeFrame::new(node, transform, ui_scale.0);
+            let bounds =
+                bounds_query.frame(entity, children, frame, bounds.copied().unwrap_or_default());
+            let follow = follow.copied().unwrap_or_default();
+            behavior.knob_delta(
+                &mut state,
+                frame,
+                base_frame,
+                bounds,
+                &follow,
+                time.delta_secs(),
+            );
+        },
+    );
+}
+
+/// Queries resolving the [`JoystickBaseBounds`] of joysticks.
+#[derive(SystemParam)]
+pub(crate) struct BaseBoundsQuery<'w, 's> {
+    region_query: Query<'w, 's, &'static JoystickInteractionRegion>,
+    interaction_area_query: Query<'w, 's, (), With<VirtualJoystickInteractionArea>>,
+    node_query: Query<'w, 's, (&'static ComputedNode, &'static UiGlobalTransform)>,
+    parent_query: Query<'w, 's, &'static ChildOf>,
+    window_query: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    ui_scale: Res<'w, UiScale>,
+}
+
+impl BaseBoundsQuery<'_, '_> {
+    /// The [`NodeFrame`] of `bounds` for the joystick `entity` with `frame`, [`None`] if unbounded.
+    fn frame(
+        &self,
+        entity: Entity,
+        children: &Children,
+        frame: NodeFrame,
+        bounds: JoystickBaseBounds,
+    ) -> Option<NodeFrame> {
+        let node_frame = |entity: Entity| {
+            self.node_query
+                .get(entity)
+                .ok()
+                .map(|(node, transform)| NodeFrame::new(node, transform, self.ui_scale.0))
         };
-        behavior.update(world, entity);
+        match bounds {
+            JoystickBaseBounds::Unbounded => None,
+            JoystickBaseBounds::InteractionArea => {
+                let area = InteractionArea::resolve(
+                    entity,
+                    self.region_query.get(entity).ok().copied(),
+                    self.window_query.single().ok().map(Window::size),
+                    Some(children),
+                    |area| self.node_query.contains(area),
+                    |area| self.interaction_area_query.contains(area),
+                );
+                match area {
+                    InteractionArea::Screen(rect) => Some(NodeFrame::from_rect(rect)),
+                    InteractionArea::Node(area) => node_frame(area).or(Some(frame)),
+                }
+            }
+            JoystickBaseBounds::Parent => node_frame(self.parent_query.get(entity).ok()?.parent()),
+            JoystickBaseBounds::Window => {
+                self.window_query.single().ok().map(|window| {
+                    NodeFrame::from_rect(Rect::from_corners(Vec2::ZERO, window.size()))
+                })
+            }
+        }
+    }
+}
+
+/// Update knob delta of joysticks with a [`JoystickSticky`] component.
+#[allow(clippy::type_complexity)]
+pub fn update_sticky_behaviors(
+    mut joystick_query: Query<
+        (
+            &JoystickSticky,
+            &mut VirtualJoystickState,
+            &mut JoystickStickyState,
+            &Children,
+        ),
+        With<VirtualJoystickNode>,
+    >,
+    base_query: BaseFrameQuery,
+    ui_scale: Res<UiScale>,
+) {
+    joystick_query
+        .par_iter_mut()
+        .for_each(|(behavior, mut state, mut sticky_state, children)| {
+            if let Some(base_frame) = base_frame(children, &base_query, ui_scale.0) {
+                behavior.knob_delta(&mut state, &mut sticky_state, base_frame);
+            }
+        });
+}
+
+/// Apply the [`JoystickDeadZone`] component of joysticks to their delta.
+pub fn update_dead_zone_behaviors(
+    mut joystick_query: Query<
+        (&JoystickDeadZone, &mut VirtualJoystickState),
+        With<VirtualJoystickNode>,
+    >,
+) {
+    joystick_query
+        .par_iter_mut()
+        .for_each(|(behavior, mut state)| behavior.constrain(&mut state));
+}
+
+/// Lock the delta of joysticks with a [`JoystickHorizontalOnly`] or [`JoystickVerticalOnly`]
+/// component to their axis.
+#[allow(clippy::type_complexity)]
+pub fn update_axis_behaviors(
+    mut joystick_query: Query<
+        (
+            &mut VirtualJoystickState,
+            Has<JoystickHorizontalOnly>,
+            Has<JoystickVerticalOnly>,
+        ),
+        (
+            With<VirtualJoystickNode>,
+            Or<(With<JoystickHorizontalOnly>, With<JoystickVerticalOnly>)>,
+        ),
+    >,
+) {
+    joystick_query
+        .par_iter_mut()
+        .for_each(|(mut state, horizontal_only, vertical_only)| {
+            if horizontal_only {
+                state.delta.y = 0.0;
+            }
+            if vertical_only {
+                state.delta.x = 0.0;
+            }
+        });
+}
+
+/// Smooth the delta of joysticks with a [`JoystickSmoothing`] component.
+#[allow(clippy::type_complexity)]
+pub fn update_smoothing_behaviors(
+    mut joystick_query: Query<
+        (
+            &JoystickSmoothing,
+            &mut VirtualJoystickState,
+            &mut JoystickSmoothingState,
+        ),
+        With<VirtualJoystickNode>,
+    >,
+    time: Res<Time>,
+) {
+    let delta_secs = time.delta_secs();
+    joystick_query
+        .par_iter_mut()
+        .for_each(|(behavior, mut state, mut smoothed)| {
+            behavior.constrain(&mut state, &mut smoothed, delta_secs);
+        });
+}
+
+/// Show joysticks with a [`JoystickInvisible`] component only while they are touched.
+pub fn update_invisible_behaviors(
+    par_cmd: ParallelCommands,
+    mut joystick_query: Query<
+        (
+            Entity,
+            &JoystickInvisible,
+            &VirtualJoystickState,
+            &mut Visibility,
+        ),
+        With<VirtualJoystickNode>,
+    >,
+) {
+    joystick_query
+        .par_iter_mut()
+        .for_each(|(entity, behavior, state, mut visibility)| {
+            let previous = *visibility;
+            behavior.update_visibility(state, &mut visibility);
+            let visibility = *visibility;
+            if visibility != previous {
+                par_cmd.command_scope(|mut cmd| {
+                    JoystickHiddenByBehavior::track_deferred(&mut cmd.entity(entity), visibility);
+                });
+            }
+        });
+}
+
+/// Fade joysticks with a [`JoystickFade`] component in and out.
+#[allow(clippy::type_complexity)]
+pub fn update_fade_behaviors(
+    mut cmd: Commands,
+    joystick_query: Query<
+        (
+            Entity,
+            &JoystickFade,
+            &VirtualJoystickState,
+            &mut JoystickFadeState,
+            &mut Visibility,
+            Has<JoystickVisualStates>,
+            &Children,
+        ),
+        With<VirtualJoystickNode>,
+    >,
+    part_query: Query<PartColorQuery>,
+    time: Res<Time>,
+) {
+    for (entity, behavior, state, mut fade_state, mut visibility, has_visual_states, children) in
+        joystick_query
+    {
+        // Keep the current colors of the joystick while fading.
+        if !has_visual_states {
+            cmd.entity(entity).insert((
+                current_visual_states(children, &part_query),
+                JoystickFadeVisualStates,
+            ));
+        }
+
+        let touched = state.touch_state.is_some();
+        let new_visibility = behavior.fade(touched, &mut fade_state, time.delta_secs());
+        if visibility.set_if_neq(new_visibility) {
+            JoystickHiddenByBehavior::track_deferred(&mut cmd.entity(entity), new_visibility);
+        }
     }
 }
 
 /// Update [`crate::VirtualJoystickAction`] from [`VirtualJoystickState`].
-pub fn update_action<S: VirtualJoystickID>(world: &mut World) {
-    let mut joysticks =
-        world.query::<(Entity, &VirtualJoystickNode<S>, &mut VirtualJoystickState)>();
+pub fn update_action(world: &mut World) {
+    let mut joysticks = world.query::<(Entity, &VirtualJoystickNode, &mut VirtualJoystickState)>();
     let joysticks: Vec<_> = joysticks.iter(world).collect();
 
     // Collect actions to be executed
@@ -214,27 +737,255 @@ pub fn update_action<S: VirtualJoystickID>(world: &mut World) {
     }
 }
 
+/// Release the active touch of every joystick right away, sending a
+/// [`VirtualJoystickMessageType::Up`] and calling [`crate::VirtualJoystickAction::on_end_drag`].
+///
+/// Used to cleanly end drags on state transitions, where the joystick systems may stop running.
+pub fn release_joysticks(world: &mut World) {
+    let mut joysticks = world.query_filtered::<Entity, With<VirtualJoystickNode>>();
+    let joysticks: Vec<_> = joysticks.iter(world).collect();
+
+    for entity in joysticks {
+        release_joystick(world, entity);
+    }
+}
+
+/// Release the active touch of the joysticks of the plugin of `S` right away, see
+/// [`release_joysticks`].
+pub(crate) fn release_plugin_joysticks<S: VirtualJoystickID>(world: &mut World) {
+    let mut joysticks =
+        world.query_filtered::<(Entity, Has<TypedJoystick>), With<VirtualJoystickNode<S>>>();
+    let joysticks: Vec<_> = joysticks
+        .iter(world)
+        .filter(|&(_, typed)| is_plugin_joystick::<S>(typed))
+        .map(|(entity, _)| entity)
+        .collect();
+
+    for entity in joysticks {
+        release_joystick(world, entity);
+    }
+}
+
+/// Release the active touch of the joystick `entity` right away, see [`release_joysticks`].
+pub(crate) fn release_joystick(world: &mut World, entity: Entity) {
+    let Some((id, action)) = world
+        .get::<VirtualJoystickNode>(entity)
+        .map(|node| (node.id.clone(), Arc::clone(&node.action)))
+    else {
+        return;
+    };
+    let Some(mut state) = world.get_mut::<VirtualJoystickState>(entity) else {
+        return;
+    };
+    if state.touch_state.take().is_none() {
+        return;
+    }
+    state.just_released = true;
+
+    // NOTE: `update_input` clears `just_released` before the behaviors may run again, so the
+    //       release is applied to their state here.
+    release_behavior_state(world, entity);
+
+    let mut state = world.get_mut::<VirtualJoystickState>(entity).unwrap();
+    state.delta = Vec2::ZERO;
+    state.base_offset = Vec2::ZERO;
+    let released = state.clone();
+    // NOTE: The release is sent below, so the joystick systems must not end the drag again.
+    state.just_released = false;
+    state.velocity = Vec2::ZERO;
+    state.acceleration = Vec2::ZERO;
+    state.travel = 0.;
+
+    // NOTE: `update_fixed_input` may not run while the joystick systems are gated, e.g. after
+    //       leaving a state, so the release is accumulated for the next fixed step here.
+    if let Some(mut fixed_input) = world.get_resource_mut::<FixedJoystickInput>()
+        && let Some(pending) = fixed_input.pending.get_mut(&entity)
+        && pending.pressed
+    {
+        pending.delta = Vec2::ZERO;
+        pending.pressed = false;
+        pending.just_released = true;
+    }
+
+    world.write_message(VirtualJoystickMessage {
+        id: id.clone(),
+        entity,
+        message_type: VirtualJoystickMessageType::Up,
+        value: Vec2::ZERO,
+        delta: Vec2::ZERO,
+        velocity: released.velocity,
+        acceleration: released.acceleration,
+        travel: released.travel,
+    });
+    action.on_end_drag(id, released, world, entity);
+}
+
 /// Send [VirtualJoystickMessage]s from [`VirtualJoystickState`].
-pub fn update_send_messages<S: VirtualJoystickID>(
-    joystick_query: Query<(&VirtualJoystickNode<S>, &VirtualJoystickState)>,
-    mut writer: MessageWriter<VirtualJoystickMessage<S>>,
+pub fn update_send_messages(
+    joystick_query: Query<(
+        Entity,
+        &VirtualJoystickNode,
+        &VirtualJoystickState,
+        &UiGlobalTransform,
+        Option<&JoystickOutputSpace>,
+    )>,
+    mut writer: MessageWriter<VirtualJoystickMessage>,
 ) {
-    for (joystick, state) in joystick_query {
+    for (entity, joystick, state, transform, output_space) in joystick_query {
         let id = joystick.id.clone();
-        let delta = state.delta;
+        let delta = match output_space {
+            Some(JoystickOutputSpace::Screen) => screen_axis(state.delta, transform),
+            _ => state.delta,
+        };
         let Some((message_type, value)) = message_type_and_value(state) else {
             continue;
         };
 
         writer.write(VirtualJoystickMessage {
             id,
+            entity,
             message_type,
             value,
             delta,
+            velocity: state.velocity,
+            acceleration: state.acceleration,
+            travel: state.travel,
         });
     }
 }
 
+/// Accumulate the input of every joystick for the next fixed step in [`FixedJoystickInput`].
+#[allow(clippy::type_complexity)]
+pub fn update_fixed_input(
+    joystick_query: Query<(
+        Entity,
+        &VirtualJoystickNode,
+        &VirtualJoystickState,
+        &UiGlobalTransform,
+        Option<&JoystickOutputSpace>,
+    )>,
+    mut fixed_input: ResMut<FixedJoystickInput>,
+) {
+    fixed_input
+        .pending
+        .retain(|entity, _| joystick_query.contains(*entity));
+
+    for (entity, joystick, state, transform, output_space) in joystick_query {
+        let pending = fixed_input.pending.entry(entity).or_default();
+        let pressed = state.touch_state.is_some();
+
+        // NOTE: Edges are derived from `pressed` so releases outside of `update_input` count too.
+        pending.id = joystick.id.clone();
+        pending.delta = match output_space {
+            Some(JoystickOutputSpace::Screen) => screen_axis(state.delta, transform),
+            _ => state.delta,
+        };
+        pending.just_pressed |= pressed && !pending.pressed;
+        pending.just_released |= !pressed && pending.pressed;
+        pending.just_canceled |= state.just_canceled;
+        pending.pressed = pressed;
+    }
+}
+
+/// Latch the accumulated [`FixedJoystickInput`] at the start of a fixed step.
+pub fn latch_fixed_input(mut fixed_input: ResMut<FixedJoystickInput>) {
+    let FixedJoystickInput { latched, pending } = &mut *fixed_input;
+    latched.clone_from(pending);
+    for pending in pending.values_mut() {
+        pending.just_pressed = false;
+        pending.just_released = false;
+        pending.just_canceled = false;
+    }
+}
+
+/// Recognize [`JoystickGesture`]s for joysticks with [`JoystickGestures`] and send them as
+/// [`VirtualJoystickGestureMessage`]s.
+#[allow(clippy::type_complexity)]
+pub fn update_gestures(
+    joystick_query: Query<(
+        Entity,
+        &VirtualJoystickNode,
+        &VirtualJoystickState,
+        &JoystickGestures,
+        &mut JoystickGestureState,
+        Has<JoystickDisabled>,
+    )>,
+    mut writer: MessageWriter<VirtualJoystickGestureMessage>,
+    time: Res<Time>,
+    pause: Res<JoystickPause>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, joystick, state, gestures, mut gesture_state, disabled) in joystick_query {
+        // Touches released by disabling the joystick or canceled are not gestures.
+        if disabled || pause.paused || state.just_canceled {
+            gesture_state.touch = None;
+            continue;
+        }
+        if let Some(touch_state) = &state.touch_state {
+            if touch_state.just_pressed {
+                gesture_state.travel = 0.;
+                gesture_state.long_press_sent = false;
+            }
+            gesture_state.touch =
+                Some((touch_state.start, touch_state.current, touch_state.duration));
+            gesture_state.travel = gesture_state
+                .travel
+                .max(touch_state.start.distance(touch_state.current));
+
+            // Send a long press once the touch was held in place for long enough.
+            if !gesture_state.long_press_sent
+                && touch_state.duration >= gestures.long_press_duration
+                && gesture_state.travel <= gestures.tap_max_distance
+            {
+                gesture_state.long_press_sent = true;
+                writer.write(VirtualJoystickGestureMessage {
+                    id: joystick.id.clone(),
+                    entity,
+                    gesture: JoystickGesture::LongPress,
+                    position: touch_state.current,
+                });
+            }
+            continue;
+        }
+
+        if !state.just_released {
+            continue;
+        }
+        let Some((start, end, duration)) = gesture_state.touch.take() else {
+            continue;
+        };
+
+        let gesture = if gesture_state.long_press_sent {
+            None
+        } else if duration <= gestures.tap_max_duration
+            && gesture_state.travel <= gestures.tap_max_distance
+        {
+            // A tap following a recent tap is promoted to a double tap.
+            if gesture_state
+                .last_tap
+                .is_some_and(|last_tap| now - last_tap <= gestures.double_tap_interval)
+            {
+                gesture_state.last_tap = None;
+                Some(JoystickGesture::DoubleTap)
+            } else {
+                gesture_state.last_tap = Some(now);
+                Some(JoystickGesture::Tap)
+            }
+        } else {
+            flick_gesture(gestures, end - start, state.velocity, duration)
+        };
+
+        if let Some(gesture) = gesture {
+            writer.write(VirtualJoystickGestureMessage {
+                id: joystick.id.clone(),
+                entity,
+                gesture,
+                position: end,
+            });
+        }
+    }
+}
+
 /// Update visual representation of the joysticks by interpreting [`VirtualJoystickState`].
 #[allow(clippy::complexity)]
 pub fn update_ui(
@@ -249,22 +1000,60 @@ pub fn update_ui(
             Without<VirtualJoystickUIBackground>,
         ),
     >,
-    joystick_query: Query<(&VirtualJoystickState, &Children)>,
+    mut joystick_indicator_query: Query<
+        (
+            &mut Node,
+            &mut UiTransform,
+            &mut Visibility,
+            &ComputedNode,
+            Has<VirtualJoystickUIDirection>,
+            Has<VirtualJoystickUIBand>,
+        ),
+        (
+            Or<(
+                With<VirtualJoystickUIDirection>,
+                With<VirtualJoystickUIBand>,
+                With<VirtualJoystickUIIntensity>,
+            )>,
+            Without<VirtualJoystickUIKnob>,
+            Without<VirtualJoystickUIBackground>,
+        ),
+    >,
+    joystick_query: Query<(
+        &VirtualJoystickState,
+        Option<&JoystickSmoothingState>,
+        Has<JoystickSmoothing>,
+        Has<JoystickSmoothedByBehavior>,
+        &Children,
+    )>,
+    ui_scale: Res<UiScale>,
 ) {
-    for (joystick_state, children) in joystick_query {
+    for (joystick_state, smoothing_state, smoothing, smoothed_by_behavior, children) in
+        joystick_query
+    {
+        // Prefer smoothed placement if the joystick is smoothed.
+        let (delta, base_offset) = match smoothing_state {
+            Some(smoothed) if smoothing || smoothed_by_behavior => {
+                (smoothed.delta, smoothed.base_offset)
+            }
+            _ => (joystick_state.delta, joystick_state.base_offset),
+        };
+        // NOTE: `base_offset` is in logical pixels, while `Val::Px` is scaled by `UiScale` again.
+        let base_offset = base_offset / ui_scale.0;
+
         let Some(base) = children
             .iter()
             .find(|entity| joystick_base_query.contains(**entity))
         else {
-            return;
+            continue;
         };
         let (mut base_style, base_node, base_global_transform) =
             joystick_base_query.get_mut(*base).unwrap();
 
-        // Adjust position of base to match `joystick_state.base_offset`
+        // Adjust position of base to match `base_offset`
         base_style.position_type = PositionType::Absolute;
-        base_style.left = Val::Px(joystick_state.base_offset.x);
-        base_style.top = Val::Px(joystick_state.base_offset.y);
+        base_style.left = Val::Px(base_offset.x);
+        base_style.top = Val::Px(base_offset.y);
 
         // NOTE: This uses 1. for `ui_scale` to avoid double scaling since
         //       this is later used in a calculation of a `Node` position.
@@ -275,7 +1064,7 @@ pub fn update_ui(
             .iter()
             .find(|entity| joystick_knob_query.contains(**entity))
         else {
-            return;
+            continue;
         };
         let (mut knob_style, knob_node, knob_global_transform) =
             joystick_knob_query.get_mut(*knob).unwrap();
@@ -285,30 +1074,586 @@ pub fn update_ui(
             node_rect(knob_node, knob_global_transform.translation, 1.).half_size();
 
         // Adjust position of knob to match correct axial movement.
-        let delta = joystick_state.delta;
         // NOTE: We are inverting y to align with user intent because `offset` is reversed on the y axis.
         let delta = Vec2::new(delta.x, -delta.y);
-        let Vec2 { x, y } = joystick_state.base_offset
-            + base_rect_half_size
-            + knob_rect_half_size
-            + base_rect_half_size * (delta - 1.);
+        let base_center = base_offset + base_rect_half_size;
+        let knob_offset = base_rect_half_size * delta;
+        // NOTE: The knob is centered on `base_center + knob_offset`, so `knob_rect_half_size` is
+        //       subtracted to get its top left corner.
+        let Vec2 { x, y } = base_center + knob_offset - knob_rect_half_size;
         knob_style.position_type = PositionType::Absolute;
         knob_style.left = Val::Px(x);
         knob_style.top = Val::Px(y);
+
+        // Point indicators from the center of the base towards the knob.
+        // NOTE: Rotating the up direction `(0., -1.)` clockwise by `angle` points it along `knob_offset`.
+        let angle = knob_offset.x.atan2(-knob_offset.y);
+        let magnitude = delta.length().min(1.);
+        for &child in children {
+            let Ok((mut style, mut transform, mut visibility, node, is_direction, is_band)) =
+                joystick_indicator_query.get_mut(child)
+            else {
+                continue;
+            };
+            let mut half_size = node_rect(node, Vec2::ZERO, 1.).half_size();
+
+            let (center, rotation, scale) = if is_direction {
+                // Sit on the rim of the base.
+                let direction = Vec2::from_angle(angle - FRAC_PI_2);
+                (
+                    base_center + direction * base_rect_half_size.x,
+                    Rot2::radians(angle),
+                    Vec2::ONE,
+                )
+            } else if is_band {
+                // Stretch the band from the base center to the knob.
+                let length = knob_offset.length();
+                style.height = Val::Px(length);
+                half_size.y = length * 0.5;
+                (
+                    base_center + knob_offset * 0.5,
+                    Rot2::radians(angle),
+                    Vec2::ONE,
+                )
+            } else {
+                // Scale the ring with the magnitude of the delta.
+                (base_center, Rot2::IDENTITY, Vec2::splat(magnitude))
+            };
+
+            let Vec2 { x, y } = center - half_size;
+            style.position_type = PositionType::Absolute;
+            style.left = Val::Px(x);
+            style.top = Val::Px(y);
+            transform.set_if_neq(UiTransform {
+                rotation,
+                scale,
+                ..*transform
+            });
+            visibility.set_if_neq(if magnitude > 0. {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            });
+        }
+    }
+}
+
+/// Apply the [`crate::JoystickStyle`] named by [`JoystickThemeStyle`] from the [`JoystickTheme`]
+/// whenever either changes.
+///
+/// Colors and opacity are handed to [`JoystickVisualStates`] so state changes are tweened.
+#[allow(clippy::type_complexity)]
+pub fn update_theme(
+    mut cmd: Commands,
+    theme: Res<JoystickTheme>,
+    ui_scale: Res<UiScale>,
+    joystick_query: Query<(
+        Entity,
+        Ref<JoystickThemeStyle>,
+        Option<&mut JoystickVisualStates>,
+        &Children,
+    )>,
+    mut part_query: Query<
+        (
+            Has<VirtualJoystickUIKnob>,
+            Option<&mut ImageNode>,
+            &mut Node,
+        ),
+        Or<(
+            With<VirtualJoystickUIKnob>,
+            With<VirtualJoystickUIBackground>,
+        )>,
+    >,
+) {
+    for (entity, theme_style, visual_states, children) in joystick_query {
+        if !theme.is_changed() && !theme_style.is_changed() && !ui_scale.is_changed() {
+            continue;
+        }
+        let Some(style) = theme.style(&theme_style.0) else {
+            continue;
+        };
+
+        match visual_states {
+            Some(mut visual_states) => visual_states.set_style(style),
+            None => {
+                let mut visual_states = JoystickVisualStates::default();
+                visual_states.set_style(style);
+                cmd.entity(entity).insert(visual_states);
+            }
+        }
+
+        for &child in children {
+            let Ok((is_knob, image_node, mut node)) = part_query.get_mut(child) else {
+                continue;
+            };
+            let (image, size) = if is_knob {
+                (&style.knob_image, style.knob_size)
+            } else {
+                (&style.background_image, style.background_size)
+            };
+            if let Some(mut image_node) = image_node
+                && let Some(image) = image
+            {
+                image_node.image = image.clone();
+            }
+            // NOTE: Sizes are in logical pixels, while `Val::Px` is scaled by `UiScale` again.
+            if let Some(size) = size {
+                node.width = Val::Px(size.x / ui_scale.0);
+                node.height = Val::Px(size.y / ui_scale.0);
+            }
+        }
+    }
+}
+
+/// Give disabled joysticks without [`JoystickVisualStates`] some keeping their colors, so they
+/// still show the [`JoystickVisualState::Disabled`] tint.
+#[allow(clippy::type_complexity)]
+pub fn update_missing_visual_states(
+    mut cmd: Commands,
+    joystick_query: Query<
+        (Entity, Has<JoystickDisabled>, &Children),
+        (With<VirtualJoystickNode>, Without<JoystickVisualStates>),
+    >,
+    part_query: Query<PartColorQuery>,
+    pause: Res<JoystickPause>,
+) {
+    for (entity, disabled, children) in &joystick_query {
+        if !disabled && !pause.paused {
+            continue;
+        }
+        cmd.entity(entity)
+            .insert(current_visual_states(children, &part_query));
+    }
+}
+
+/// Colors of joystick parts only faded by [`JoystickVisualStates`], see [`set_part_opacity`].
+type PartOpacityQuery = (
+    Option<&'static mut BackgroundColor>,
+    Option<&'static mut ImageNode>,
+    Option<&'static mut BorderColor>,
+    Option<&'static mut Outline>,
+    Option<&'static mut JoystickPartOpacity>,
+);
+
+/// Tween joysticks with [`JoystickVisualStates`] towards the visual of their current [`JoystickVisualState`].
+#[allow(clippy::type_complexity)]
+pub fn update_visual_states(
+    mut cmd: Commands,
+    joystick_query: Query<(
+        Entity,
+        &JoystickVisualStates,
+        &mut JoystickVisualTransition,
+        &VirtualJoystickState,
+        Option<&JoystickFadeState>,
+        Has<JoystickFadeVisualStates>,
+        Has<JoystickDisabled>,
+        &Children,
+    )>,
+    mut part_query: Query<
+        (
+            Entity,
+            Has<VirtualJoystickUIKnob>,
+            Option<&mut ImageNode>,
+            Option<&mut BackgroundColor>,
+            Option<&mut BorderColor>,
+            Option<&mut Outline>,
+            Option<&mut JoystickPartOpacity>,
+            &mut UiTransform,
+        ),
+        Or<(
+            With<VirtualJoystickUIKnob>,
+            With<VirtualJoystickUIBackground>,
+        )>,
+    >,
+    mut descendant_query: Query<
+        PartOpacityQuery,
+        (
+            Without<VirtualJoystickUIKnob>,
+            Without<VirtualJoystickUIBackground>,
+        ),
+    >,
+    children_query: Query<&Children>,
+    time: Res<Time>,
+    pause: Res<JoystickPause>,
+) {
+    for (
+        joystick,
+        visual_states,
+        mut transition,
+        joystick_state,
+        fade_state,
+        fade_visual_states,
+        disabled,
+        children,
+    ) in joystick_query
+    {
+        let state = if disabled || pause.paused {
+            JoystickVisualState::Disabled
+        } else {
+            JoystickVisualState::from_joystick(joystick_state, visual_states.drag_threshold)
+        };
+        let target = visual_states.visual(state);
+        let applied = transition.current;
+
+        // Start a new transition from the current visual, or snap to the first state.
+        match transition.state {
+            None => transition.from = *target,
+            Some(previous) if previous != state => {
+                transition.from = transition.current;
+                transition.elapsed = 0.;
+            }
+            _ => transition.elapsed += time.delta_secs(),
+        }
+        transition.state = Some(state);
+        let t = visual_states
+            .ease
+            .sample_clamped(transition.elapsed / visual_states.duration.max(f32::EPSILON));
+        let current = transition.from.lerp(target, t);
+        if transition.current != current {
+            transition.current = current;
+        }
+
+        for &child in children {
+            let Ok((
+                entity,
+                is_knob,
+                image_node,
+                background_color,
+                border_color,
+                outline,
+                applied_opacity,
+                mut transform,
+            )) = part_query.get_mut(child)
+            else {
+                continue;
+            };
+            let (part, applied) = if is_knob {
+                (current.knob, applied.knob)
+            } else {
+                (current.background, applied.background)
+            };
+            let opacity = part.opacity * fade_state.map_or(1., |fade_state| fade_state.opacity);
+            let color = part.color.with_alpha(part.color.alpha() * opacity);
+
+            // Only write changed values to keep change detection quiet.
+            if let Some(mut image_node) = image_node {
+                if image_node.color != color {
+                    image_node.color = color;
+                }
+            } else if let Some(mut background_color) = background_color
+                && background_color.0 != color
+            {
+                background_color.0 = color;
+            }
+            // Borders, outlines and descendants like the arrows of procedural joysticks keep
+            // their colors and are only faded.
+            set_part_opacity(
+                &mut cmd,
+                entity,
+                opacity,
+                (None, None, border_color, outline, applied_opacity),
+            );
+            fade_descendants(
+                &mut cmd,
+                entity,
+                opacity,
+                &children_query,
+                &mut descendant_query,
+            );
+            // Scale relative to the previously applied visual to keep the scale set by users.
+            if part.scale != applied.scale {
+                let user_scale = if applied.scale != 0. {
+                    transform.scale / applied.scale
+                } else {
+                    Vec2::ONE
+                };
+                transform.scale = user_scale * part.scale;
+            }
+        }
+
+        // Fade the rest of the joystick too, like its interaction area and indicators.
+        let fade = fade_state.map_or(1., |fade_state| fade_state.opacity);
+        if let Ok(colors) = descendant_query.get_mut(joystick) {
+            set_part_opacity(&mut cmd, joystick, fade, colors);
+        }
+        for &child in children {
+            if part_query.contains(child) {
+                continue;
+            }
+            if let Ok(colors) = descendant_query.get_mut(child) {
+                set_part_opacity(&mut cmd, child, fade, colors);
+            }
+            fade_descendants(
+                &mut cmd,
+                child,
+                fade,
+                &children_query,
+                &mut descendant_query,
+            );
+        }
+
+        // Drop the visual states of a fade that is gone, now that its opacity is undone.
+        if fade_visual_states && fade_state.is_none() {
+            cmd.entity(joystick).remove::<(
+                JoystickVisualStates,
+                JoystickVisualTransition,
+                JoystickFadeVisualStates,
+                JoystickPartOpacity,
+            )>();
+            for entity in children_query.iter_descendants(joystick) {
+                cmd.entity(entity).try_remove::<JoystickPartOpacity>();
+            }
+        }
+    }
+}
+
+/// Fade the colors of the descendants of `entity` to `opacity`, see [`set_part_opacity`].
+fn fade_descendants(
+    cmd: &mut Commands,
+    entity: Entity,
+    opacity: f32,
+    children_query: &Query<&Children>,
+    descendant_query: &mut Query<
+        PartOpacityQuery,
+        (
+            Without<VirtualJoystickUIKnob>,
+            Without<VirtualJoystickUIBackground>,
+        ),
+    >,
+) {
+    for descendant in children_query.iter_descendants(entity) {
+        if let Ok(colors) = descendant_query.get_mut(descendant) {
+            set_part_opacity(cmd, descendant, opacity, colors);
+        }
+    }
+}
+
+/// Multiply `opacity` into the alpha of the colors of the part `entity`, relative to the opacity
+/// last applied to them in its [`JoystickPartOpacity`] so the colors set by users are kept.
+fn set_part_opacity(
+    cmd: &mut Commands,
+    entity: Entity,
+    opacity: f32,
+    (background_color, image_node, border_color, outline, applied): QueryItem<
+        '_,
+        '_,
+        PartOpacityQuery,
+    >,
+) {
+    // NOTE: Opacity is kept above zero so it can be divided out again.
+    let opacity = opacity.max(1e-3);
+    let previous = applied.as_ref().map_or(1., |applied| applied.0);
+    if previous == opacity {
+        return;
+    }
+    match applied {
+        Some(mut applied) => applied.0 = opacity,
+        None => {
+            cmd.entity(entity).insert(JoystickPartOpacity(opacity));
+        }
+    }
+
+    let factor = opacity / previous;
+    let mut fade = |color: &mut Color| color.set_alpha(color.alpha() * factor);
+    if let Some(mut background_color) = background_color {
+        fade(&mut background_color.0);
+    }
+    if let Some(mut image_node) = image_node {
+        fade(&mut image_node.color);
+    }
+    if let Some(mut border_color) = border_color {
+        let BorderColor {
+            top,
+            right,
+            bottom,
+            left,
+        } = &mut *border_color;
+        [top, right, bottom, left].into_iter().for_each(&mut fade);
+    }
+    if let Some(mut outline) = outline {
+        fade(&mut outline.color);
+    }
+}
+
+/// Resize joysticks with a [`JoystickSize`] for the primary window.
+#[allow(clippy::type_complexity)]
+pub fn update_sizes(
+    window: Single<&Window, With<PrimaryWindow>>,
+    ui_scale: Res<UiScale>,
+    joystick_query: Query<(&JoystickSize, &Children, &mut Node)>,
+    mut part_query: Query<
+        (Has<VirtualJoystickUIKnob>, &mut Node),
+        (
+            Or<(
+                With<VirtualJoystickUIKnob>,
+                With<VirtualJoystickUIBackground>,
+            )>,
+            Without<JoystickSize>,
+        ),
+    >,
+) {
+    // NOTE: Sizes resolve in logical pixels, while `Val::Px` is scaled by `UiScale` again.
+    let resize = |node: &mut Node, dimension: Option<JoystickDimension>| {
+        let Some(dimension) = dimension else {
+            return;
+        };
+        let size = Val::Px(dimension.resolve(Some(&window)) / ui_scale.0);
+        if node.width != size || node.height != size {
+            node.width = size;
+            node.height = size;
+        }
+    };
+
+    for (size, children, mut node) in joystick_query {
+        resize(&mut node, size.joystick);
+        for &child in children {
+            let Ok((is_knob, mut node)) = part_query.get_mut(child) else {
+                continue;
+            };
+            resize(&mut node, if is_knob { size.knob } else { size.background });
+        }
+    }
+}
+
+/// Place joysticks with a [`JoystickAnchor`] inside the [`JoystickSafeArea`] of the primary window.
+pub fn update_anchors(
+    window: Single<Ref<Window>, With<PrimaryWindow>>,
+    safe_area: Res<JoystickSafeArea>,
+    ui_scale: Res<UiScale>,
+    joystick_query: Query<(Ref<JoystickAnchor>, Ref<ComputedNode>, &mut Node)>,
+) {
+    let environment_changed =
+        window.is_changed() || safe_area.is_changed() || ui_scale.is_changed();
+    // NOTE: The safe area is computed in logical pixels and converted to `Val::Px` units at the end.
+    let min = Vec2::new(safe_area.left, safe_area.top);
+    let max = Vec2::new(
+        window.width() - safe_area.right,
+        window.height() - safe_area.bottom,
+    );
+    let size = (max - min).max(Vec2::ZERO);
+
+    for (anchor, computed_node, mut node) in joystick_query {
+        if !environment_changed && !anchor.is_changed() && !computed_node.is_changed() {
+            continue;
+        }
+        let alignment = anchor.point.alignment();
+        let margin = anchor.margin.resolve(size);
+        let node_size = computed_node.size() * computed_node.inverse_scale_factor * ui_scale.0;
+
+        let (left, right) = anchor_offsets(
+            alignment.x,
+            (min.x, max.x),
+            window.width(),
+            margin.x,
+            node_size.x,
+        );
+        let (top, bottom) = anchor_offsets(
+            alignment.y,
+            (min.y, max.y),
+            window.height(),
+            margin.y,
+            node_size.y,
+        );
+        let (left, right, top, bottom) = (
+            left / ui_scale.0,
+            right / ui_scale.0,
+            top / ui_scale.0,
+            bottom / ui_scale.0,
+        );
+
+        // Only write changed values to keep change detection quiet.
+        if node.position_type != PositionType::Absolute
+            || node.left != left
+            || node.right != right
+            || node.top != top
+            || node.bottom != bottom
+        {
+            node.position_type = PositionType::Absolute;
+            node.left = left;
+            node.right = right;
+            node.top = top;
+            node.bottom = bottom;
+        }
+    }
+}
+
+/// Start and end offsets in logical pixels of a node anchored at `alignment` on one axis of the
+/// safe area `(min, max)` of a window `extent` wide.
+fn anchor_offsets(
+    alignment: f32,
+    (min, max): (f32, f32),
+    extent: f32,
+    margin: f32,
+    size: f32,
+) -> (Val, Val) {
+    if alignment <= 0. {
+        (Val::Px(min + margin), Val::Auto)
+    } else if alignment >= 1. {
+        (Val::Auto, Val::Px(extent - max + margin))
+    } else {
+        (
+            Val::Px(min + (max - min - size) * alignment + margin),
+            Val::Auto,
+        )
     }
 }
 
+/// Whether a logical `point` lies within a [`ComputedNode`], honoring the rotation and scale of its transform.
+fn node_contains(
+    node: &ComputedNode,
+    transform: &UiGlobalTransform,
+    point: Vec2,
+    ui_scale: f32,
+) -> bool {
+    node.contains_point(*transform, point / (node.inverse_scale_factor * ui_scale))
+}
+
+/// Rotate a delta from the local space of a joystick into screen space.
+fn screen_axis(delta: Vec2, transform: &UiGlobalTransform) -> Vec2 {
+    let (_, angle, _) = transform.to_scale_angle_translation();
+    // NOTE: We are inverting y around the rotation because the UI rotates with the y axis pointing down.
+    let Vec2 { x, y } = Rot2::radians(angle) * Vec2::new(delta.x, -delta.y);
+    Vec2::new(x, -y)
+}
+
 /// The [`Rect`] of a [`ComputedNode`].
 fn node_rect(node: &ComputedNode, translation: Vec2, ui_scale: f32) -> Rect {
     let factor = node.inverse_scale_factor * ui_scale;
     Rect::from_center_size(translation * factor, node.size() * factor)
 }
 
+/// The [`JoystickGesture::Flick`] for a released touch that moved by `offset` within `duration`,
+/// if it was released fast enough.
+fn flick_gesture(
+    gestures: &JoystickGestures,
+    offset: Vec2,
+    velocity: Vec2,
+    duration: f32,
+) -> Option<JoystickGesture> {
+    if duration > gestures.flick_max_duration || offset.length() <= gestures.tap_max_distance {
+        return None;
+    }
+    let speed = velocity.length();
+    // NOTE: A still pointer has no direction, even if `flick_min_speed` is zero.
+    if speed <= f32::EPSILON || speed < gestures.flick_min_speed {
+        return None;
+    }
+
+    // NOTE: We are inverting y to align with user intent because `velocity` is reversed on the y axis.
+    let Vec2 { x, y } = velocity / speed;
+    Some(JoystickGesture::Flick {
+        direction: Vec2::new(x, -y),
+        speed,
+    })
+}
+
 /// The appropriate [`VirtualJoystickMessageType`] and the appropriate [`VirtualJoystickMessage::value`] from [`VirtualJoystickState`].
 fn message_type_and_value(
     state: &VirtualJoystickState,
 ) -> Option<(VirtualJoystickMessageType, Vec2)> {
-    if state.just_released {
+    if state.just_canceled {
+        Some((VirtualJoystickMessageType::Cancel, Vec2::ZERO))
+    } else if state.just_released {
         Some((VirtualJoystickMessageType::Up, Vec2::ZERO))
     } else {
         state.touch_state.as_ref().map(|touch_state| {
@@ -320,3 +1665,237 @@ fn message_type_and_value(
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::{message::Messages, system::RunSystemOnce};
+
+    use super::*;
+    use crate::JoystickId;
+
+    /// A world with a joystick recognizing gestures.
+    fn gesture_world() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<JoystickPause>();
+        world.init_resource::<Messages<VirtualJoystickGestureMessage>>();
+        let entity = world
+            .spawn((
+                VirtualJoystickNode::<JoystickId>::default(),
+                VirtualJoystickState::default(),
+                JoystickGestures::default(),
+            ))
+            .id();
+        (world, entity)
+    }
+
+    /// Run [`update_gestures`] with the touch of the joystick set by `touch`, returning the
+    /// recognized gestures.
+    fn step(
+        world: &mut World,
+        entity: Entity,
+        touch: impl FnOnce(&mut VirtualJoystickState),
+    ) -> Vec<JoystickGesture> {
+        touch(&mut world.get_mut::<VirtualJoystickState>(entity).unwrap());
+        world.run_system_once(update_gestures).unwrap();
+        world
+            .resource_mut::<Messages<VirtualJoystickGestureMessage>>()
+            .drain()
+            .map(|message| message.gesture())
+            .collect()
+    }
+
+    fn press(state: &mut VirtualJoystickState) {
+        state.touch_state = Some(TouchState::from_touch_pos(0, Vec2::ZERO));
+    }
+
+    fn hold(duration: f32, current: Vec2) -> impl FnOnce(&mut VirtualJoystickState) {
+        move |state| {
+            let touch_state = state.touch_state.as_mut().unwrap();
+            touch_state.just_pressed = false;
+            touch_state.duration = duration;
+            touch_state.current = current;
+        }
+    }
+
+    fn release(velocity: Vec2) -> impl FnOnce(&mut VirtualJoystickState) {
+        move |state| {
+            state.touch_state = None;
+            state.just_released = true;
+            state.velocity = velocity;
+        }
+    }
+
+    #[test]
+    fn release_joysticks_releases_fixed_input() {
+        let mut world = World::new();
+        world.init_resource::<FixedJoystickInput>();
+        world.init_resource::<Messages<VirtualJoystickMessage>>();
+        let entity = world
+            .spawn((
+                VirtualJoystickNode::<JoystickId>::default(),
+                VirtualJoystickState {
+                    touch_state: Some(TouchState::from_touch_pos(0, Vec2::ZERO)),
+                    delta: Vec2::X,
+                    ..Default::default()
+                },
+                UiGlobalTransform::default(),
+            ))
+            .id();
+        world.run_system_once(update_fixed_input).unwrap();
+        world.run_system_once(latch_fixed_input).unwrap();
+        assert!(
+            world
+                .resource::<FixedJoystickInput>()
+                .entity(entity)
+                .unwrap()
+                .pressed
+        );
+
+        // NOTE: `update_fixed_input` does not run after, like while leaving a state.
+        world.run_system_once(release_joysticks).unwrap();
+        world.run_system_once(latch_fixed_input).unwrap();
+        let fixed_input = world.resource::<FixedJoystickInput>();
+        let state = fixed_input.entity(entity).unwrap();
+        assert!(!state.pressed && state.just_released);
+        assert_eq!(state.delta, Vec2::ZERO);
+    }
+
+    #[test]
+    fn release_joysticks_releases_behavior_state() {
+        let mut world = World::new();
+        world.init_resource::<Messages<VirtualJoystickMessage>>();
+        let entity = world
+            .spawn((
+                VirtualJoystickNode::<JoystickId>::default(),
+                VirtualJoystickState {
+                    touch_state: Some(TouchState::from_touch_pos(0, Vec2::ZERO)),
+                    delta: Vec2::X,
+                    ..Default::default()
+                },
+                JoystickSticky::default(),
+                JoystickSmoothing::default(),
+                JoystickSmoothingState {
+                    delta: Vec2::X,
+                    ..Default::default()
+                },
+            ))
+            .id();
+
+        world.run_system_once(release_joysticks).unwrap();
+        let entity = world.entity(entity);
+        let state = entity.get::<VirtualJoystickState>().unwrap();
+        assert!(state.touch_state.is_none() && !state.just_released);
+        assert_eq!(state.delta, Vec2::ZERO);
+        assert_eq!(entity.get::<JoystickStickyState>().unwrap().held, Vec2::X);
+        let smoothed = entity.get::<JoystickSmoothingState>().unwrap();
+        assert!(smoothed.release.is_some());
+        assert_eq!(smoothed.delta, Vec2::X);
+    }
+
+    #[test]
+    fn recognizes_tap_and_double_tap() {
+        let (mut world, entity) = gesture_world();
+        assert_eq!(step(&mut world, entity, press), []);
+        assert_eq!(
+            step(&mut world, entity, release(Vec2::ZERO)),
+            [JoystickGesture::Tap]
+        );
+        step(&mut world, entity, press);
+        assert_eq!(
+            step(&mut world, entity, release(Vec2::ZERO)),
+            [JoystickGesture::DoubleTap]
+        );
+    }
+
+    #[test]
+    fn recognizes_long_press_once_without_tap() {
+        let (mut world, entity) = gesture_world();
+        step(&mut world, entity, press);
+        assert_eq!(step(&mut world, entity, hold(0.2, Vec2::ZERO)), []);
+        assert_eq!(
+            step(&mut world, entity, hold(0.6, Vec2::ZERO)),
+            [JoystickGesture::LongPress]
+        );
+        assert_eq!(step(&mut world, entity, hold(0.8, Vec2::ZERO)), []);
+        assert_eq!(step(&mut world, entity, release(Vec2::ZERO)), []);
+    }
+
+    #[test]
+    fn recognizes_flick() {
+        let (mut world, entity) = gesture_world();
+        step(&mut world, entity, press);
+        step(&mut world, entity, hold(0.1, Vec2::new(100., 0.)));
+        assert_eq!(
+            step(&mut world, entity, release(Vec2::new(0., -1000.))),
+            [JoystickGesture::Flick {
+                direction: Vec2::Y,
+                speed: 1000.,
+            }]
+        );
+    }
+
+    #[test]
+    fn slow_or_long_swipes_are_not_flicks() {
+        let gestures = JoystickGestures {
+            flick_min_speed: 0.,
+            ..Default::default()
+        };
+        let offset = Vec2::new(100., 0.);
+        assert_eq!(flick_gesture(&gestures, offset, Vec2::ZERO, 0.1), None);
+        assert_eq!(
+            flick_gesture(&JoystickGestures::default(), offset, Vec2::X * 100., 0.1),
+            None
+        );
+        assert_eq!(
+            flick_gesture(&JoystickGestures::default(), offset, Vec2::X * 1000., 0.5),
+            None
+        );
+    }
+
+    /// The knob position `update_ui` places for a knob of `knob_size` on a base of `base_size`.
+    fn knob_position(knob_size: f32, base_size: f32, delta: Vec2) -> Vec2 {
+        let mut world = World::new();
+        world.init_resource::<UiScale>();
+        let computed_node = |size: f32| ComputedNode {
+            size: Vec2::splat(size),
+            ..Default::default()
+        };
+        let knob = world
+            .spawn((
+                VirtualJoystickUIKnob,
+                Node::default(),
+                computed_node(knob_size),
+            ))
+            .id();
+        let base = world
+            .spawn((
+                VirtualJoystickUIBackground,
+                Node::default(),
+                computed_node(base_size),
+            ))
+            .id();
+        world
+            .spawn(VirtualJoystickState {
+                delta,
+                ..Default::default()
+            })
+            .add_children(&[base, knob]);
+        world.run_system_once(update_ui).unwrap();
+
+        let node = world.get::<Node>(knob).unwrap();
+        let (Val::Px(left), Val::Px(top)) = (node.left, node.top) else {
+            panic!("the knob is not placed in pixels");
+        };
+        Vec2::new(left, top)
+    }
+
+    #[test]
+    fn knob_is_centered_on_its_base() {
+        // NOTE: Knobs half the size of their base were the only ones centered before.
+        assert_eq!(knob_position(75., 150., Vec2::ZERO), Vec2::splat(37.5));
+        assert_eq!(knob_position(40., 100., Vec2::ZERO), Vec2::splat(30.));
+        assert_eq!(knob_position(40., 100., Vec2::X), Vec2::new(80., 30.));
+        assert_eq!(knob_position(40., 100., Vec2::Y), Vec2::new(30., -20.));
+    }
+}
@@ -0,0 +1,109 @@
+use bevy::{
+    asset::Handle,
+    color::{Alpha, Color, ColorToComponents, LinearRgba},
+    ecs::{
+        component::Component, reflect::ReflectComponent, reflect::ReflectResource,
+        resource::Resource,
+    },
+    image::Image,
+    platform::collections::HashMap,
+    prelude::Vec2,
+    reflect::{Reflect, std_traits::ReflectDefault},
+};
+
+/// Named [`JoystickStyle`]s shared by the joysticks of a game.
+///
+/// Joysticks opt in with a [`JoystickThemeStyle`] naming their style, and are restyled whenever
+/// the theme changes.
+#[derive(Resource, Clone, Debug, Default, Reflect)]
+#[reflect(Resource, Default)]
+pub struct JoystickTheme {
+    pub styles: HashMap<String, JoystickStyle>,
+}
+
+impl JoystickTheme {
+    /// Add or replace the style called `name`.
+    pub fn with_style(mut self, name: impl Into<String>, style: JoystickStyle) -> Self {
+        self.styles.insert(name.into(), style);
+        self
+    }
+
+    /// Get the style called `name`.
+    pub fn style(&self, name: &str) -> Option<&JoystickStyle> {
+        self.styles.get(name)
+    }
+}
+
+/// Appearance of a joystick in a [`JoystickTheme`].
+///
+/// Colors and opacity are applied through [`crate::JoystickVisualStates`], which is inserted if
+/// missing. Images and sizes left as `None` are kept as spawned.
+#[derive(Clone, Debug, Reflect)]
+#[reflect(Default)]
+pub struct JoystickStyle {
+    pub knob_colors: JoystickColors,
+    pub background_colors: JoystickColors,
+    pub knob_image: Option<Handle<Image>>,
+    pub background_image: Option<Handle<Image>>,
+    /// Size of the knob in logical pixels.
+    pub knob_size: Option<Vec2>,
+    /// Size of the background in logical pixels.
+    pub background_size: Option<Vec2>,
+    /// Opacity multiplied into every color of the style.
+    pub opacity: f32,
+}
+
+impl Default for JoystickStyle {
+    fn default() -> Self {
+        Self {
+            knob_colors: JoystickColors::default(),
+            background_colors: JoystickColors::default(),
+            knob_image: None,
+            background_image: None,
+            knob_size: None,
+            background_size: None,
+            opacity: 1.,
+        }
+    }
+}
+
+/// Colors of a joystick part for each of its states.
+#[derive(Clone, Copy, Debug, Reflect)]
+#[reflect(Default)]
+pub struct JoystickColors {
+    pub idle: Color,
+    pub pressed: Color,
+    pub disabled: Color,
+}
+
+impl JoystickColors {
+    /// The same `color` for every state.
+    pub fn all(color: Color) -> Self {
+        Self {
+            idle: color,
+            pressed: color,
+            disabled: color,
+        }
+    }
+
+    /// `color` tinted with the default [`Self::disabled`] color, for joysticks without a style.
+    pub(crate) fn default_disabled(color: Color) -> Color {
+        let tint = Self::default().disabled.to_linear().to_vec4();
+        LinearRgba::from_vec4(color.to_linear().to_vec4() * tint).into()
+    }
+}
+
+impl Default for JoystickColors {
+    fn default() -> Self {
+        Self {
+            idle: Color::WHITE,
+            pressed: Color::WHITE,
+            disabled: Color::WHITE.with_alpha(0.3),
+        }
+    }
+}
+
+/// Name of the [`JoystickStyle`] in the [`JoystickTheme`] used by a joystick.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickThemeStyle(pub String);
@@ -0,0 +1,218 @@
+use std::{any::TypeId, sync::Arc};
+
+use bevy::{
+    ecs::{
+        change_detection::{DetectChanges, Ref},
+        component::Component,
+        entity::Entity,
+        lifecycle::{Add, Remove},
+        message::{MessageReader, MessageWriter},
+        observer::On,
+        query::Changed,
+        reflect::ReflectComponent,
+        system::{Commands, Query, Res, ResMut},
+        world::{EntityWorldMut, World},
+    },
+    reflect::Reflect,
+};
+
+use crate::{
+    FixedJoystickInput, JoystickId, VirtualJoystickAction, VirtualJoystickBehavior,
+    VirtualJoystickGestureMessage, VirtualJoystickID, VirtualJoystickMessage, VirtualJoystickNode,
+    VirtualJoystickState,
+};
+
+/// Typed id of a joystick spawned with a [`VirtualJoystickNode<S>`] of a [`VirtualJoystickID`]
+/// other than [`JoystickId`].
+///
+/// Typed joysticks keep their [`VirtualJoystickNode<S>`] and run through the runtime pipeline
+/// with a [`VirtualJoystickNode`] next to it, with [`JoystickId::from_typed`] as id. The typed
+/// node is the source of truth, changes to it are copied to the runtime node before the input
+/// is read. This keeps the typed id to forward their messages as [`VirtualJoystickMessage<S>`].
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct JoystickTypedId<S: VirtualJoystickID>(pub S);
+
+/// Marks the runtime [`VirtualJoystickNode`] of a typed joystick, see [`JoystickTypedId`].
+#[derive(Component, Clone, Copy)]
+pub(crate) struct TypedJoystick {
+    /// Write a behavior set on the runtime node back to the typed node.
+    set_behavior: fn(&mut EntityWorldMut, Arc<dyn VirtualJoystickBehavior>),
+}
+
+impl TypedJoystick {
+    fn new<S: VirtualJoystickID>() -> Self {
+        Self {
+            set_behavior: |entity, behavior| {
+                if let Some(mut node) = entity.get_mut::<VirtualJoystickNode<S>>() {
+                    node.behavior = behavior;
+                }
+            },
+        }
+    }
+
+    /// Set the behavior of the typed node of `entity`, if it is a typed joystick.
+    pub(crate) fn set_behavior(
+        entity: &mut EntityWorldMut,
+        behavior: Arc<dyn VirtualJoystickBehavior>,
+    ) {
+        if let Some(typed) = entity.get::<TypedJoystick>().copied() {
+            (typed.set_behavior)(entity, behavior);
+        }
+    }
+}
+
+/// Whether a joystick with a [`VirtualJoystickNode<S>`] belongs to the plugin of `S`.
+///
+/// Typed joysticks also have a [`VirtualJoystickNode`], they belong to the plugin of their
+/// typed id instead.
+pub(crate) fn is_plugin_joystick<S: VirtualJoystickID>(typed: bool) -> bool {
+    !typed || TypeId::of::<S>() != TypeId::of::<JoystickId>()
+}
+
+/// [`VirtualJoystickAction`] of a typed joystick, called with its typed id.
+pub(crate) struct TypedAction<S> {
+    id: S,
+    action: Arc<dyn VirtualJoystickAction<S>>,
+}
+
+impl<S> TypedAction<S> {
+    pub(crate) fn new(id: S, action: Arc<dyn VirtualJoystickAction<S>>) -> Self {
+        Self { id, action }
+    }
+}
+
+impl<S: VirtualJoystickID> VirtualJoystickAction<JoystickId> for TypedAction<S> {
+    fn on_start_drag(
+        &self,
+        _id: JoystickId,
+        data: VirtualJoystickState,
+        world: &mut World,
+        entity: Entity,
+    ) {
+        self.action
+            .on_start_drag(self.id.clone(), data, world, entity);
+    }
+
+    fn on_drag(
+        &self,
+        _id: JoystickId,
+        data: VirtualJoystickState,
+        world: &mut World,
+        entity: Entity,
+    ) {
+        self.action.on_drag(self.id.clone(), data, world, entity);
+    }
+
+    fn on_end_drag(
+        &self,
+        _id: JoystickId,
+        data: VirtualJoystickState,
+        world: &mut World,
+        entity: Entity,
+    ) {
+        self.action
+            .on_end_drag(self.id.clone(), data, world, entity);
+    }
+}
+
+/// The runtime [`VirtualJoystickNode`] mirroring a typed `node`.
+fn runtime_node<S: VirtualJoystickID>(node: &VirtualJoystickNode<S>) -> VirtualJoystickNode {
+    VirtualJoystickNode {
+        id: JoystickId::from_typed(&node.id),
+        behavior: Arc::clone(&node.behavior),
+        action: Arc::new(TypedAction::new(node.id.clone(), Arc::clone(&node.action))),
+    }
+}
+
+/// Hand a [`VirtualJoystickNode<S>`] over to the runtime pipeline, see [`JoystickTypedId`].
+pub(crate) fn into_runtime_joystick<S: VirtualJoystickID>(
+    add: On<Add, VirtualJoystickNode<S>>,
+    mut cmd: Commands,
+) {
+    cmd.entity(add.entity).queue(|mut entity: EntityWorldMut| {
+        let Some(node) = entity.get::<VirtualJoystickNode<S>>() else {
+            return;
+        };
+        let bundle = (
+            runtime_node(node),
+            JoystickTypedId(node.id.clone()),
+            TypedJoystick::new::<S>(),
+        );
+        entity.insert(bundle);
+    });
+}
+
+/// Remove the runtime [`VirtualJoystickNode`] of a typed joystick with its typed node.
+pub(crate) fn remove_runtime_joystick<S: VirtualJoystickID>(
+    remove: On<Remove, VirtualJoystickNode<S>>,
+    mut cmd: Commands,
+) {
+    // NOTE: This also runs when the joystick is despawned.
+    cmd.entity(remove.entity)
+        .try_remove::<(VirtualJoystickNode, JoystickTypedId<S>, TypedJoystick)>();
+}
+
+/// Copy changes of typed [`VirtualJoystickNode<S>`]s to their runtime [`VirtualJoystickNode`].
+#[allow(clippy::type_complexity)]
+pub(crate) fn sync_typed_joysticks<S: VirtualJoystickID>(
+    joystick_query: Query<
+        (
+            Ref<VirtualJoystickNode<S>>,
+            &mut VirtualJoystickNode,
+            &mut JoystickTypedId<S>,
+        ),
+        Changed<VirtualJoystickNode<S>>,
+    >,
+) {
+    for (node, mut runtime, mut typed_id) in joystick_query {
+        // NOTE: Newly added nodes were just mirrored by `into_runtime_joystick`.
+        if node.is_added() {
+            continue;
+        }
+        *runtime = runtime_node(&node);
+        typed_id.0 = node.id.clone();
+    }
+}
+
+/// Forward the [`VirtualJoystickMessage`]s of typed joysticks as [`VirtualJoystickMessage<S>`].
+pub(crate) fn forward_messages<S: VirtualJoystickID>(
+    mut reader: MessageReader<VirtualJoystickMessage>,
+    mut writer: MessageWriter<VirtualJoystickMessage<S>>,
+    ids: Query<&JoystickTypedId<S>>,
+) {
+    for message in reader.read() {
+        if let Ok(id) = ids.get(message.entity()) {
+            writer.write(message.with_id(id.0.clone()));
+        }
+    }
+}
+
+/// Forward the [`VirtualJoystickGestureMessage`]s of typed joysticks as
+/// [`VirtualJoystickGestureMessage<S>`].
+pub(crate) fn forward_gestures<S: VirtualJoystickID>(
+    mut reader: MessageReader<VirtualJoystickGestureMessage>,
+    mut writer: MessageWriter<VirtualJoystickGestureMessage<S>>,
+    ids: Query<&JoystickTypedId<S>>,
+) {
+    for message in reader.read() {
+        if let Ok(id) = ids.get(message.entity()) {
+            writer.write(message.with_id(id.0.clone()));
+        }
+    }
+}
+
+/// Latch the [`FixedJoystickInput`] of typed joysticks into [`FixedJoystickInput<S>`].
+pub(crate) fn latch_typed_fixed_input<S: VirtualJoystickID>(
+    fixed_input: Res<FixedJoystickInput>,
+    mut typed_input: ResMut<FixedJoystickInput<S>>,
+    ids: Query<&JoystickTypedId<S>>,
+) {
+    typed_input.latched = fixed_input
+        .iter()
+        .filter_map(|(entity, state)| {
+            let id = ids.get(entity).ok()?;
+            Some((entity, state.with_id(id.0.clone())))
+        })
+        .collect();
+}
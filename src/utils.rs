@@ -1,9 +1,10 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
 use crate::components::VirtualJoystickInteractionArea;
 use crate::{
     VirtualJoystickAction, VirtualJoystickBehavior, VirtualJoystickBundle, VirtualJoystickID,
-    VirtualJoystickNode, VirtualJoystickUIBackground, VirtualJoystickUIKnob,
+    VirtualJoystickNode, VirtualJoystickUIBackground, VirtualJoystickUIBand,
+    VirtualJoystickUIDirection, VirtualJoystickUIIntensity, VirtualJoystickUIKnob,
 };
 
 /// This function is a simple helper to create a joystick
@@ -20,6 +21,8 @@ use crate::{
 /// * `joystick_node`: [`JoystickNode`] struct
 /// * `joystick_node_style`: bevy Style for joystick
 ///
+/// Returns the [`Entity`] of the joystick, e.g. to insert a [`crate::JoystickThemeStyle`].
+///
 /// Usage:
 /// ```no_run
 /// create_joystick(
@@ -111,32 +114,17 @@ pub fn create_joystick<I: VirtualJoystickID>(
     joystick_node_style: Node,
     behavior: impl VirtualJoystickBehavior,
     action: impl VirtualJoystickAction<I>,
-) {
-    let mut spawn = cmd.spawn(
-        VirtualJoystickBundle::new(
-            VirtualJoystickNode::<I>::default()
-                .with_id(id)
-                .with_behavior(behavior)
-                .with_action(action),
-        )
-        .set_style(joystick_node_style),
+) -> Entity {
+    let mut spawn = spawn_joystick_root(
+        cmd,
+        id,
+        interactable_area_color,
+        joystick_node_style,
+        behavior,
+        action,
     );
 
-    if let Some(c) = interactable_area_color {
-        spawn.insert(BackgroundColor(c));
-    }
-
     spawn.with_children(|parent| {
-        // Interaction Area
-        parent.spawn((
-            VirtualJoystickInteractionArea,
-            Node {
-                width: Val::Percent(100.),
-                height: Val::Percent(100.),
-                ..default()
-            },
-        ));
-
         // Knob
         parent.spawn((
             VirtualJoystickUIKnob,
@@ -171,4 +159,279 @@ pub fn create_joystick<I: VirtualJoystickID>(
             ZIndex(0),
         ));
     });
+    spawn.id()
+}
+
+/// Style of a joystick drawn from plain UI nodes, see [`create_procedural_joystick`].
+///
+/// Widths are in logical pixels.
+#[derive(Clone, Debug, Reflect)]
+#[reflect(Default)]
+pub struct ProceduralJoystickStyle {
+    pub knob_color: Color,
+    pub knob_border_color: Color,
+    pub knob_border_width: f32,
+    pub background_color: Color,
+    pub background_border_color: Color,
+    pub background_border_width: f32,
+    /// Color of the outline drawn around the knob and the background.
+    pub outline_color: Color,
+    pub outline_width: f32,
+    /// Color of the direction arrows on the rim of the background, `None` hides them.
+    pub arrow_color: Option<Color>,
+    pub arrow_size: f32,
+    pub arrow_thickness: f32,
+}
+
+impl Default for ProceduralJoystickStyle {
+    fn default() -> Self {
+        Self {
+            knob_color: Color::WHITE.with_alpha(0.8),
+            knob_border_color: Color::WHITE,
+            knob_border_width: 2.,
+            background_color: Color::BLACK.with_alpha(0.3),
+            background_border_color: Color::WHITE.with_alpha(0.8),
+            background_border_width: 3.,
+            outline_color: Color::NONE,
+            outline_width: 0.,
+            arrow_color: Some(Color::WHITE.with_alpha(0.8)),
+            arrow_size: 12.,
+            arrow_thickness: 3.,
+        }
+    }
+}
+
+/// This function is a simple helper to create a joystick drawn from plain UI nodes,
+/// so no image assets are needed
+///
+/// * `cmd`: bevy Commands, this required to spawn entity
+/// * `id`: ID of the joystick
+/// * `style`: [`ProceduralJoystickStyle`] with colors and widths of the knob and the border
+/// * `interactable_area_color`: Color for interactable zone
+/// * `knob_size`: Size for knob ui
+/// * `background_size`: Size for joystick border ui
+/// * `joystick_node_style`: bevy Node for joystick
+/// * `behavior`: [`VirtualJoystickBehavior`] of the joystick
+/// * `action`: [`VirtualJoystickAction`] of the joystick
+///
+/// Returns the [`Entity`] of the joystick.
+///
+/// Usage:
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use virtual_joystick::*;
+/// # fn setup(mut cmd: Commands) {
+/// create_procedural_joystick(
+///     &mut cmd,
+///     "UniqueJoystick".to_string(),
+///     ProceduralJoystickStyle::default(),
+///     None,
+///     Vec2::new(75., 75.),
+///     Vec2::new(150., 150.),
+///     Node {
+///         width: Val::Px(150.),
+///         height: Val::Px(150.),
+///         position_type: PositionType::Absolute,
+///         left: Val::Percent(50.),
+///         bottom: Val::Percent(15.),
+///         ..default()
+///     },
+///     JoystickFloating,
+///     NoAction,
+/// );
+/// # }
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn create_procedural_joystick<I: VirtualJoystickID>(
+    cmd: &mut Commands,
+    id: I,
+    style: ProceduralJoystickStyle,
+    interactable_area_color: Option<Color>,
+    knob_size: Vec2,
+    background_size: Vec2,
+    joystick_node_style: Node,
+    behavior: impl VirtualJoystickBehavior,
+    action: impl VirtualJoystickAction<I>,
+) -> Entity {
+    let mut spawn = spawn_joystick_root(
+        cmd,
+        id,
+        interactable_area_color,
+        joystick_node_style,
+        behavior,
+        action,
+    );
+
+    spawn.with_children(|parent| {
+        // Knob
+        parent.spawn((
+            VirtualJoystickUIKnob,
+            circle_node(knob_size, style.knob_border_width),
+            BackgroundColor(style.knob_color),
+            BorderColor::all(style.knob_border_color),
+            Outline::new(Val::Px(style.outline_width), Val::ZERO, style.outline_color),
+            ZIndex(1),
+        ));
+
+        // Background
+        parent
+            .spawn((
+                VirtualJoystickUIBackground,
+                circle_node(background_size, style.background_border_width),
+                BackgroundColor(style.background_color),
+                BorderColor::all(style.background_border_color),
+                Outline::new(Val::Px(style.outline_width), Val::ZERO, style.outline_color),
+                ZIndex(0),
+            ))
+            .with_children(|parent| {
+                let Some(arrow_color) = style.arrow_color else {
+                    return;
+                };
+                // Arrows are squares with two borders, forming a chevron once rotated.
+                let radius =
+                    background_size.x.min(background_size.y) * 0.5 - style.background_border_width;
+                for (direction, angle) in [
+                    (Vec2::NEG_Y, 45_f32),
+                    (Vec2::X, 135.),
+                    (Vec2::Y, 225.),
+                    (Vec2::NEG_X, 315.),
+                ] {
+                    let Vec2 { x, y } = background_size * 0.5 - style.background_border_width
+                        + direction * (radius - style.arrow_size)
+                        - style.arrow_size * 0.5;
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            left: Val::Px(x),
+                            top: Val::Px(y),
+                            width: Val::Px(style.arrow_size),
+                            height: Val::Px(style.arrow_size),
+                            border: UiRect {
+                                left: Val::Px(style.arrow_thickness),
+                                top: Val::Px(style.arrow_thickness),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        BorderColor::all(arrow_color),
+                        UiTransform::from_rotation(Rot2::degrees(angle)),
+                    ));
+                }
+            });
+    });
+    spawn.id()
+}
+
+/// This function is a simple helper to add a [`VirtualJoystickUIDirection`], a [`VirtualJoystickUIBand`]
+/// and a [`VirtualJoystickUIIntensity`] drawn from plain UI nodes to a joystick
+///
+/// * `cmd`: bevy Commands, this required to spawn entity
+/// * `joystick`: [`Entity`] of the joystick, as returned by [`create_joystick`]
+/// * `color`: Color for the indicators
+/// * `background_size`: Size for joystick border ui, used for the intensity ring
+pub fn create_joystick_indicators(
+    cmd: &mut Commands,
+    joystick: Entity,
+    color: Color,
+    background_size: Vec2,
+) {
+    cmd.entity(joystick).with_children(|parent| {
+        // Direction, a chevron pointing up before being rotated
+        parent
+            .spawn((
+                VirtualJoystickUIDirection,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(20.),
+                    height: Val::Px(20.),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Node {
+                        width: Val::Px(12.),
+                        height: Val::Px(12.),
+                        border: UiRect {
+                            left: Val::Px(3.),
+                            top: Val::Px(3.),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    BorderColor::all(color),
+                    UiTransform::from_rotation(Rot2::degrees(45.)),
+                ));
+            });
+
+        // Band
+        parent.spawn((
+            VirtualJoystickUIBand,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(6.),
+                border_radius: BorderRadius::MAX,
+                ..default()
+            },
+            BackgroundColor(color.with_alpha(color.alpha() * 0.5)),
+        ));
+
+        // Intensity
+        parent.spawn((
+            VirtualJoystickUIIntensity,
+            circle_node(background_size, 2.),
+            BorderColor::all(color),
+        ));
+    });
+}
+
+/// Spawn the root [`Entity`] of a joystick with its [`VirtualJoystickInteractionArea`].
+fn spawn_joystick_root<'a, I: VirtualJoystickID>(
+    cmd: &'a mut Commands,
+    id: I,
+    interactable_area_color: Option<Color>,
+    joystick_node_style: Node,
+    behavior: impl VirtualJoystickBehavior,
+    action: impl VirtualJoystickAction<I>,
+) -> EntityCommands<'a> {
+    let mut spawn = cmd.spawn(
+        VirtualJoystickBundle::new(
+            VirtualJoystickNode::<I>::default()
+                .with_id(id)
+                .with_behavior(behavior)
+                .with_action(action),
+        )
+        .set_style(joystick_node_style),
+    );
+
+    if let Some(c) = interactable_area_color {
+        spawn.insert(BackgroundColor(c));
+    }
+
+    spawn.with_children(|parent| {
+        // Interaction Area
+        parent.spawn((
+            VirtualJoystickInteractionArea,
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                ..default()
+            },
+        ));
+    });
+    spawn
+}
+
+/// A round [`Node`] of `size` with a border of `border_width`.
+fn circle_node(size: Vec2, border_width: f32) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Px(size.x),
+        height: Val::Px(size.y),
+        border: UiRect::all(Val::Px(border_width)),
+        border_radius: BorderRadius::MAX,
+        ..default()
+    }
 }
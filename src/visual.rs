@@ -0,0 +1,200 @@
+use bevy::{
+    color::{Color, Mix},
+    ecs::{component::Component, reflect::ReflectComponent},
+    math::curve::EaseFunction,
+    reflect::{Reflect, std_traits::ReflectDefault},
+};
+
+use crate::{JoystickStyle, VirtualJoystickState};
+
+/// Visual state of a joystick, derived from its [`VirtualJoystickState`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default)]
+pub enum JoystickVisualState {
+    #[default]
+    Idle,
+    /// Touched but the knob has not left [`JoystickVisualStates::drag_threshold`] yet.
+    Pressed,
+    Dragging,
+    /// The joystick has a [`crate::JoystickDisabled`] or joysticks are paused with [`crate::JoystickPause`].
+    Disabled,
+}
+
+impl JoystickVisualState {
+    /// The visual state matching `state`.
+    pub fn from_joystick(state: &VirtualJoystickState, drag_threshold: f32) -> Self {
+        if state.touch_state.is_none() {
+            Self::Idle
+        } else if state.delta.length() > drag_threshold {
+            Self::Dragging
+        } else {
+            Self::Pressed
+        }
+    }
+}
+
+/// Appearance of a joystick part targeted by a [`JoystickVisualState`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default)]
+pub struct JoystickPartVisual {
+    pub color: Color,
+    /// Multiplied into the scale of the [`bevy::ui::UiTransform`] of the part.
+    pub scale: f32,
+    /// Opacity multiplied into [`Self::color`].
+    pub opacity: f32,
+}
+
+impl Default for JoystickPartVisual {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            scale: 1.,
+            opacity: 1.,
+        }
+    }
+}
+
+impl JoystickPartVisual {
+    /// Interpolate towards `other` by `t`.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            color: self.color.mix(&other.color, t),
+            scale: self.scale + (other.scale - self.scale) * t,
+            opacity: self.opacity + (other.opacity - self.opacity) * t,
+        }
+    }
+}
+
+/// Appearance of the knob and background of a joystick in a [`JoystickVisualState`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default)]
+pub struct JoystickVisual {
+    pub knob: JoystickPartVisual,
+    pub background: JoystickPartVisual,
+}
+
+impl JoystickVisual {
+    /// Interpolate towards `other` by `t`.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            knob: self.knob.lerp(&other.knob, t),
+            background: self.background.lerp(&other.background, t),
+        }
+    }
+}
+
+/// Built-in visual feedback for a joystick, tweening its knob and background between the
+/// [`JoystickVisual`] of each [`JoystickVisualState`].
+///
+/// Colors only tint the [`bevy::ui::widget::ImageNode`] of image based joysticks and the
+/// [`bevy::ui::BackgroundColor`] of procedural ones. Their opacity also fades the
+/// [`bevy::ui::BorderColor`] and [`bevy::ui::Outline`] of the knob and background and every
+/// color of their descendants, like the arrows of procedural joysticks, which otherwise keep
+/// their own colors. Scales multiply the scale of their [`bevy::ui::UiTransform`].
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+#[require(JoystickVisualTransition)]
+pub struct JoystickVisualStates {
+    pub idle: JoystickVisual,
+    pub pressed: JoystickVisual,
+    pub dragging: JoystickVisual,
+    pub disabled: JoystickVisual,
+    /// Time in seconds a transition between two states takes.
+    pub duration: f32,
+    pub ease: EaseFunction,
+    /// Length of [`VirtualJoystickState::delta`] from which a press counts as dragging.
+    pub drag_threshold: f32,
+}
+
+impl Default for JoystickVisualStates {
+    fn default() -> Self {
+        Self::from_colors(Color::WHITE, Color::WHITE)
+    }
+}
+
+impl JoystickVisualStates {
+    /// Visual states keeping the given colors, with a slightly grown knob while touched and
+    /// faded out while disabled.
+    pub fn from_colors(knob: Color, background: Color) -> Self {
+        let idle = JoystickVisual {
+            knob: JoystickPartVisual {
+                color: knob,
+                ..Default::default()
+            },
+            background: JoystickPartVisual {
+                color: background,
+                ..Default::default()
+            },
+        };
+        let mut pressed = idle;
+        pressed.knob.scale = 1.1;
+        let mut disabled = idle;
+        disabled.knob.opacity = 0.4;
+        disabled.background.opacity = 0.4;
+
+        Self {
+            idle,
+            pressed,
+            dragging: pressed,
+            disabled,
+            duration: 0.12,
+            ease: EaseFunction::CubicOut,
+            drag_threshold: 0.1,
+        }
+    }
+
+    /// Take the colors and opacity of a [`JoystickStyle`] for every state.
+    pub fn set_style(&mut self, style: &JoystickStyle) {
+        for (visual, knob, background) in [
+            (
+                &mut self.idle,
+                style.knob_colors.idle,
+                style.background_colors.idle,
+            ),
+            (
+                &mut self.pressed,
+                style.knob_colors.pressed,
+                style.background_colors.pressed,
+            ),
+            (
+                &mut self.dragging,
+                style.knob_colors.pressed,
+                style.background_colors.pressed,
+            ),
+            (
+                &mut self.disabled,
+                style.knob_colors.disabled,
+                style.background_colors.disabled,
+            ),
+        ] {
+            visual.knob.color = knob;
+            visual.knob.opacity = style.opacity;
+            visual.background.color = background;
+            visual.background.opacity = style.opacity;
+        }
+    }
+
+    /// The [`JoystickVisual`] targeted in `state`.
+    pub fn visual(&self, state: JoystickVisualState) -> &JoystickVisual {
+        match state {
+            JoystickVisualState::Idle => &self.idle,
+            JoystickVisualState::Pressed => &self.pressed,
+            JoystickVisualState::Dragging => &self.dragging,
+            JoystickVisualState::Disabled => &self.disabled,
+        }
+    }
+}
+
+/// Progress of the transition driven by [`JoystickVisualStates`].
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct JoystickVisualTransition {
+    /// State transitioned to, `None` until the first update.
+    pub state: Option<JoystickVisualState>,
+    /// Visual at the start of the transition.
+    pub from: JoystickVisual,
+    /// Visual currently applied.
+    pub current: JoystickVisual,
+    /// Time in seconds since the transition started.
+    pub elapsed: f32,
+}